@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use docx_meta_update::{
+    load_custom_properties, load_metadata, load_record, parse_date, records_to_csv, CoreMetadata,
+    CustomProperty, DocxMetaError, MetadataRecord,
+};
+use serde::Serialize;
+
+use crate::config;
+use crate::i18n::{fill, tr, Language, Text};
+
+// --- Headless Mode ---
+// `docx-meta-update --read file.docx --format json` prints the metadata
+// and exits without opening a window. It only ever reads the file.
+// `--scan <folder>` does the same for every document under a folder tree,
+// listing those matching `--older-than` and `--author` (as CSV by default).
+
+#[derive(Serialize)]
+struct MetadataReport {
+    path: String,
+    #[serde(flatten)]
+    metadata: CoreMetadata,
+    custom: Vec<CustomProperty>,
+}
+
+// A document is listed only when it passes every filter given
+#[derive(Default)]
+struct ScanFilter {
+    // Modified before this; a missing or unreadable dcterms:modified also
+    // counts, as nothing shows the document is newer
+    older_than: Option<DateTime<Utc>>,
+    author: Option<String>, // Lowercased; matched within creator or lastModifiedBy
+}
+
+impl ScanFilter {
+    fn matches(&self, record: &MetadataRecord) -> bool {
+        let stale = self.older_than.is_none_or(|threshold| {
+            parse_date(&record.metadata.modified).is_none_or(|modified| modified < threshold)
+        });
+        let by_author = self.author.as_ref().is_none_or(|pattern| {
+            record.authors.creator.to_lowercase().contains(pattern)
+                || record
+                    .authors
+                    .last_modified_by
+                    .to_lowercase()
+                    .contains(pattern)
+        });
+        stale && by_author
+    }
+}
+
+// Returns the exit code, or None when no headless flag was given and the
+// GUI should start
+pub fn run(args: &[String]) -> Option<i32> {
+    let language = config::load_preferences().language;
+    let missing = |flag: &str| {
+        usage(
+            language,
+            &fill(tr(language, Text::CliMissingValue), &[flag]),
+        )
+    };
+    let mut read = None;
+    let mut scan = None;
+    let mut format = None;
+    let mut filter = ScanFilter::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--read" => read = Some(args.next().map(PathBuf::from)),
+            "--scan" => scan = Some(args.next().map(PathBuf::from)),
+            "--format" => match args.next() {
+                Some(value) => format = Some(value.clone()),
+                None => return Some(missing("--format")),
+            },
+            "--older-than" => match args.next() {
+                Some(value) => match parse_date(value) {
+                    Some(date) => filter.older_than = Some(date),
+                    None => {
+                        return Some(usage(
+                            language,
+                            &fill(tr(language, Text::CliUnrecognizedDate), &[value]),
+                        ))
+                    }
+                },
+                None => return Some(missing("--older-than")),
+            },
+            "--author" => match args.next() {
+                Some(value) => filter.author = Some(value.to_lowercase()),
+                None => return Some(missing("--author")),
+            },
+            _ => {}
+        }
+    }
+    if let Some(dir) = scan {
+        let Some(dir) = dir else {
+            return Some(missing("--scan"));
+        };
+        return Some(scan_folder(
+            &dir,
+            &filter,
+            format.as_deref().unwrap_or("csv"),
+            language,
+        ));
+    }
+    let path = match read? {
+        Some(path) => path,
+        None => return Some(missing("--read")),
+    };
+    let format = format.unwrap_or_else(|| "json".to_string());
+    if format != "json" {
+        return Some(unsupported_format(language, &format));
+    }
+    let json = read_report(&path)
+        .map_err(|e| crate::error_message(language, &e))
+        .and_then(|report| serde_json::to_string_pretty(&report).map_err(|e| e.to_string()));
+    Some(match json {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            1
+        }
+    })
+}
+
+fn read_report(path: &Path) -> Result<MetadataReport, DocxMetaError> {
+    Ok(MetadataReport {
+        path: path.display().to_string(),
+        metadata: load_metadata(path)?,
+        custom: load_custom_properties(path)?,
+    })
+}
+
+// --- Folder Scan ---
+
+fn scan_folder(dir: &Path, filter: &ScanFilter, format: &str, language: Language) -> i32 {
+    if format != "csv" && format != "json" {
+        return unsupported_format(language, format);
+    }
+    if !dir.is_dir() {
+        let dir = dir.display().to_string();
+        eprintln!("{}", fill(tr(language, Text::CliNotAFolder), &[&dir]));
+        return 1;
+    }
+    let mut files = Vec::new();
+    collect_documents(dir, &mut files, language);
+    // Unreadable documents are reported and left out, not fatal
+    let records: Vec<MetadataRecord> = files
+        .iter()
+        .filter_map(|path| match load_record(path) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                let path = path.display().to_string();
+                let e = crate::error_message(language, &e);
+                eprintln!("{}", fill(tr(language, Text::CliSkipped), &[&path, &e]));
+                None
+            }
+        })
+        .filter(|record| filter.matches(record))
+        .collect();
+    if format == "json" {
+        match serde_json::to_string_pretty(&records) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1;
+            }
+        }
+    } else {
+        print!("{}", records_to_csv(&records));
+    }
+    0
+}
+
+// Depth-first in name order. Symlinks are not followed, so a link back up
+// the tree can't loop, and Office's "~$" owner files are skipped.
+fn collect_documents(dir: &Path, files: &mut Vec<PathBuf>, language: Language) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            let (dir, e) = (dir.display().to_string(), e.to_string());
+            eprintln!(
+                "{}",
+                fill(tr(language, Text::CliUnreadableFolder), &[&dir, &e])
+            );
+            return;
+        }
+    };
+    let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_documents(&path, files, language);
+        } else if file_type.is_file()
+            && crate::is_supported_document(&path)
+            && !entry.file_name().to_string_lossy().starts_with("~$")
+        {
+            files.push(path);
+        }
+    }
+}
+
+fn unsupported_format(language: Language, format: &str) -> i32 {
+    usage(
+        language,
+        &fill(tr(language, Text::CliUnsupportedFormat), &[format]),
+    )
+}
+
+fn usage(language: Language, error: &str) -> i32 {
+    eprintln!("{}", error);
+    eprintln!("{}", tr(language, Text::CliUsage));
+    2
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::PathBuf;
+
+use docx_meta_update::{CompressionLevel, DateProfile};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Language;
+
+// --- On-disk Configuration ---
+// Everything lives under <OS config dir>/docx-meta-update/. Persisting is
+// best-effort: a missing or unreadable file just means starting fresh.
+
+const RECENT_FILES_LIMIT: usize = 10;
+
+fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("docx-meta-update"))
+}
+
+fn recent_files_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("recent_files.json"))
+}
+
+fn preferences_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("preferences.json"))
+}
+
+// Where the theme was kept before it moved into the preferences
+fn theme_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("theme.json"))
+}
+
+fn write_json<T: Serialize + ?Sized>(path: Option<PathBuf>, value: &T) -> Result<(), String> {
+    let path = path.ok_or("找不到配置目录。")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+// Entries whose files have since been moved or deleted are dropped
+pub fn load_recent_files() -> Vec<PathBuf> {
+    let Some(json) = recent_files_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let files: Vec<PathBuf> = serde_json::from_str(&json).unwrap_or_default();
+    files.into_iter().filter(|path| path.is_file()).collect()
+}
+
+pub fn save_recent_files(files: &[PathBuf]) -> Result<(), String> {
+    write_json(recent_files_path(), files)
+}
+
+// Moves `path` to the front of the list, keeping at most RECENT_FILES_LIMIT entries
+pub fn remember_recent_file(files: &mut Vec<PathBuf>, path: PathBuf) {
+    files.retain(|existing| *existing != path);
+    files.insert(0, path);
+    files.truncate(RECENT_FILES_LIMIT);
+}
+
+// --- Theme ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub fn toggled(self) -> Self {
+        match self {
+            ThemeChoice::Light => ThemeChoice::Dark,
+            ThemeChoice::Dark => ThemeChoice::Light,
+        }
+    }
+
+    pub fn theme(self) -> iced::Theme {
+        match self {
+            ThemeChoice::Light => iced::Theme::Light,
+            ThemeChoice::Dark => iced::Theme::Dark,
+        }
+    }
+}
+
+// White on black with saturated accents, for the accessibility preference
+pub fn high_contrast_theme() -> iced::Theme {
+    iced::Theme::custom(
+        "High contrast".to_string(),
+        iced::theme::Palette {
+            background: iced::Color::BLACK,
+            text: iced::Color::WHITE,
+            primary: iced::Color::from_rgb(1.0, 0.85, 0.0),
+            success: iced::Color::from_rgb(0.0, 1.0, 0.4),
+            danger: iced::Color::from_rgb(1.0, 0.35, 0.35),
+        },
+    )
+}
+
+// The range offered for Preferences::ui_scale
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 1.0..=2.0;
+
+fn load_legacy_theme() -> ThemeChoice {
+    theme_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// --- Preferences ---
+// Settings kept between sessions. Every field has a default, so a missing
+// file, or one written by an older version, still loads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: ThemeChoice,
+    pub high_contrast: bool, // Overrides `theme`
+    pub ui_scale: f32,       // Multiplies every size, text included
+    pub read_only: bool,     // Fields show as plain text and nothing is saved
+    pub language: Language,
+    pub local_time_display: bool, // Date inputs show the system timezone; values stay UTC
+    pub verify_after_save: bool,
+    pub confirm_before_save: bool,
+    pub block_date_order: bool,
+    pub check_future_dates: bool,
+    pub block_future_dates: bool,
+    pub future_date_tolerance: String, // Minutes, as typed
+    pub sync_file_times: bool,
+    pub sync_created_time: bool,
+    pub indent_xml: bool,
+    pub lf_line_endings: bool,
+    pub stamp_parts_modified: bool,
+    pub reproducible_zip: bool,
+    pub compression: CompressionLevel,
+    pub date_profile: DateProfile,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            theme: ThemeChoice::default(),
+            high_contrast: false,
+            ui_scale: 1.0,
+            read_only: false,
+            language: Language::from_system(),
+            local_time_display: false,
+            verify_after_save: true,
+            confirm_before_save: false,
+            block_date_order: false,
+            check_future_dates: false,
+            block_future_dates: false,
+            future_date_tolerance: "5".to_string(),
+            sync_file_times: false,
+            sync_created_time: false,
+            indent_xml: false,
+            lf_line_endings: false,
+            stamp_parts_modified: false,
+            reproducible_zip: false,
+            compression: CompressionLevel::default(),
+            date_profile: DateProfile::default(),
+        }
+    }
+}
+
+// Without a preferences file yet, the theme still comes from theme.json
+pub fn load_preferences() -> Preferences {
+    let json = preferences_path().and_then(|path| fs::read_to_string(path).ok());
+    match json {
+        Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+        None => Preferences {
+            theme: load_legacy_theme(),
+            ..Preferences::default()
+        },
+    }
+}
+
+pub fn save_preferences(preferences: &Preferences) -> Result<(), String> {
+    write_json(preferences_path(), preferences)
+}
@@ -0,0 +1,112 @@
+use chrono::{Datelike, Months, NaiveDate};
+use iced::{
+    theme,
+    widget::{button, column, row, text, Space},
+    Alignment, Element, Length,
+};
+
+const CELL_WIDTH: f32 = 40.0;
+
+// --- Month Calendar ---
+// Only tracks which month is on screen; the selected date lives in the
+// caller's state as a string, like every other field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calendar {
+    first_of_month: NaiveDate,
+}
+
+impl Calendar {
+    pub fn containing(date: NaiveDate) -> Self {
+        Self {
+            first_of_month: date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    pub fn previous_month(self) -> Self {
+        Self {
+            first_of_month: self
+                .first_of_month
+                .checked_sub_months(Months::new(1))
+                .unwrap_or(self.first_of_month),
+        }
+    }
+
+    pub fn next_month(self) -> Self {
+        Self {
+            first_of_month: self
+                .first_of_month
+                .checked_add_months(Months::new(1))
+                .unwrap_or(self.first_of_month),
+        }
+    }
+
+    fn days_in_month(&self) -> u32 {
+        let next = self.next_month().first_of_month;
+        if next == self.first_of_month {
+            31
+        } else {
+            (next - self.first_of_month).num_days() as u32
+        }
+    }
+
+    // `weekdays` holds the column headers starting from Monday
+    pub fn view<'a, Message: Clone + 'a>(
+        &self,
+        selected: Option<NaiveDate>,
+        weekdays: [&'a str; 7],
+        on_select: fn(NaiveDate) -> Message,
+        on_previous: Message,
+        on_next: Message,
+    ) -> Element<'a, Message> {
+        let header = row(vec![
+            button("<").on_press(on_previous).into(),
+            text(self.first_of_month.format("%Y-%m").to_string())
+                .width(Length::Fixed(CELL_WIDTH * 5.0))
+                .horizontal_alignment(iced::alignment::Horizontal::Center)
+                .into(),
+            button(">").on_press(on_next).into(),
+        ])
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let weekday_row = row(weekdays
+            .iter()
+            .map(|name| {
+                text(*name)
+                    .width(Length::Fixed(CELL_WIDTH))
+                    .horizontal_alignment(iced::alignment::Horizontal::Center)
+                    .into()
+            })
+            .collect::<Vec<_>>());
+
+        let leading_blanks = self.first_of_month.weekday().num_days_from_monday() as usize;
+        let mut cells: Vec<Element<'a, Message>> = (0..leading_blanks)
+            .map(|_| Space::with_width(Length::Fixed(CELL_WIDTH)).into())
+            .collect();
+        for day in 1..=self.days_in_month() {
+            let Some(date) = self.first_of_month.with_day(day) else {
+                continue;
+            };
+            let style = if Some(date) == selected {
+                theme::Button::Primary
+            } else {
+                theme::Button::Text
+            };
+            cells.push(
+                button(text(day).horizontal_alignment(iced::alignment::Horizontal::Center))
+                    .width(Length::Fixed(CELL_WIDTH))
+                    .style(style)
+                    .on_press(on_select(date))
+                    .into(),
+            );
+        }
+
+        let mut weeks = vec![header.into(), weekday_row.into()];
+        let mut cells = cells.into_iter().peekable();
+        while cells.peek().is_some() {
+            weeks.push(row(cells.by_ref().take(7).collect::<Vec<_>>()).into());
+        }
+
+        column(weeks).spacing(4).into()
+    }
+}
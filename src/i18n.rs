@@ -0,0 +1,887 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// --- Supported Languages ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Chinese];
+
+    // Falls back to English for any locale we don't have a table for
+    pub fn from_system() -> Self {
+        match sys_locale::get_locale() {
+            Some(locale) if locale.to_lowercase().starts_with("zh") => Language::Chinese,
+            _ => Language::English,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::English => "English",
+            Language::Chinese => "简体中文",
+        })
+    }
+}
+
+// --- Message Keys ---
+// Entries containing "{}" are templates; fill them with `fill`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    WindowTitle,
+    Welcome,
+    OpeningFileDialog,
+    LoadingFrom,
+    ScanningArchive,
+    ReadingProperties,
+    SelectionCancelled,
+    UnsupportedFileDropped,
+    FileLoaded,
+    CorePropertiesMissing,
+    AppPropertiesMissing,
+    AppXmlExisting,
+    AppXmlNew,
+    CreatingAppXml,
+    PropertyPartsMissing,
+    DocumentSummary,
+    OpenDocumentSummary,
+    MetaPartMissing,
+    OpenDocumentLoaded,
+    PartsListMismatch,
+    Yes,
+    No,
+    SignedDocumentWarning,
+    ProceedAnyway,
+    Error,
+    Saving,
+    NoFileToSave,
+    FileSaved,
+    ReloadAfterSaveFailed,
+    SaveError,
+    SaveProgress,
+    SaveCancelled,
+    Cancel,
+    Log,
+    ClearLog,
+    NoFileSelected,
+    InvalidPath,
+    SelectFile,
+    SaveChanges,
+    SaveAs,
+    TouchAndSave,
+    RevealInFolder,
+    FileNoLongerExists,
+    Revert,
+    RevertPrompt,
+    KeepEditing,
+    UnsavedChanges,
+    SaveAndClose,
+    CloseWithoutSaving,
+    FileSavedAs,
+    AppPropertiesRebuilt,
+    SelectFolder,
+    ReadOnly,
+    OpeningFolderDialog,
+    FolderSelectionCancelled,
+    NoDocumentsInFolder,
+    BatchProgress,
+    BatchReview,
+    BatchSelected,
+    SaveSelected,
+    SelectAll,
+    SelectNone,
+    BatchFileSucceeded,
+    BatchFileFailed,
+    BatchFinished,
+    ExportJson,
+    JsonExported,
+    ImportJson,
+    JsonImported,
+    ImportPreview,
+    MergeImport,
+    ApplyImport,
+    JsonFilter,
+    ExportCsv,
+    CsvFilter,
+    CsvExported,
+    CsvRowSkipped,
+    PickDate,
+    Anonymize,
+    ResetDatesOnAnonymize,
+    AnonymizeCleared,
+    StripEditingHistory,
+    EditingHistoryStripped,
+    RemoveCustomProperties,
+    RemoveCustomPropertiesPrompt,
+    CustomPropertiesRemoved,
+    RemoveThumbnail,
+    RemoveThumbnailPrompt,
+    ThumbnailRemoved,
+    AnonymizeDatesReset,
+    InvalidNeutralTimestamp,
+    ShiftBy,
+    DateShiftPlaceholder,
+    ShiftDates,
+    CopyCreatedToModified,
+    CopyModifiedToCreated,
+    DatesShifted,
+    InvalidDateShift,
+    VerifyAfterSave,
+    ConfirmBeforeSave,
+    ConfirmSave,
+    ConfirmSavePrompt,
+    ConfirmSaveHint,
+    NoFieldChanges,
+    DryRun,
+    BlockDateOrder,
+    CheckFutureDates,
+    BlockFutureDates,
+    FutureDateTolerance,
+    FutureDateSaved,
+    IndentXml,
+    LfLineEndings,
+    StampPartsModified,
+    ReproducibleZip,
+    Compression,
+    CompressionNormal,
+    CompressionStore,
+    CompressionFast,
+    CompressionBest,
+    DateProfile,
+    DateProfileWord,
+    DateProfileStrictWord,
+    DateProfileAsEntered,
+    ModifiedBeforeCreated,
+    LastPrintedBeforeCreated,
+    DryRunFinished,
+    Validate,
+    ValidationPassed,
+    ValidationFailed,
+    ChangeAdded,
+    ChangeChanged,
+    ChangeRemoved,
+    SyncFileTimes,
+    SyncCreatedTime,
+    CopyFrom,
+    PasteInto,
+    CopiedFrom,
+    MetadataCopied,
+    MetadataPasted,
+    RecentFiles,
+    DarkTheme,
+    LightTheme,
+    ShowXml,
+    HideXml,
+    ShowXmlDiff,
+    CompareDocuments,
+    HideComparison,
+    Settings,
+    HideSettings,
+    HighContrast,
+    LocalTimeDisplay,
+    UiScale,
+    ChooseComparedFile,
+    ComparisonDifferences,
+    XmlBefore,
+    XmlAfter,
+    PartMissing,
+    Language,
+    CreatedLabel,
+    ModifiedLabel,
+    LastPrintedLabel,
+    RevisionLabel,
+    ContentStatusLabel,
+    DescriptionLabel,
+    KeywordsLabel,
+    AddKeyword,
+    DocumentLanguageLabel,
+    CategoryLabel,
+    VersionLabel,
+    IdentifierLabel,
+    TemplateLabel,
+    ApplicationLabel,
+    AppVersionLabel,
+    CompanyLabel,
+    ManagerLabel,
+    TotalTimeLabel,
+    PagesLabel,
+    WordsLabel,
+    CharactersLabel,
+    DocSecurityLabel,
+    ScaleCropLabel,
+    LinksUpToDateLabel,
+    SharedDocLabel,
+    CreatedPlaceholder,
+    ModifiedPlaceholder,
+    LastPrintedPlaceholder,
+    RevisionPlaceholder,
+    ContentStatusPlaceholder,
+    InvalidNumber,
+    InvalidDocSecurity,
+    KeywordsPlaceholder,
+    DocumentLanguagePlaceholder,
+    CommonLanguages,
+    CategoryPlaceholder,
+    VersionPlaceholder,
+    IdentifierPlaceholder,
+    TemplatePlaceholder,
+    ApplicationPlaceholder,
+    AppVersionPlaceholder,
+    CompanyPlaceholder,
+    ManagerPlaceholder,
+    TotalTimePlaceholder,
+    CountPlaceholder,
+    DocSecurityPlaceholder,
+    DocumentFilter,
+    WordDocument,
+    ExcelWorkbook,
+    PowerPointPresentation,
+    OdtDocument,
+    XmlError,
+    XmlErrorAt,
+    UndecodablePart,
+    DateParseError,
+    NotWordDate,
+    CountParseError,
+    FlagParseError,
+    DocSecurityParseError,
+    AppVersionParseError,
+    DatesOutOfOrder,
+    ModifiedBeforeCreatedIssue,
+    LastPrintedBeforeCreatedIssue,
+    FutureDatesRefused,
+    PartModified,
+    ReplaceOriginalFailed,
+    WriteNewFileFailed,
+    CreateBackupFailed,
+    CrossVolumeCopyFailed,
+    RemoveTempFileFailed,
+    SetFileTimesFailed,
+    VerifyRemovedNew,
+    VerifyRestored,
+    VerifyRestoreFailed,
+    CliMissingValue,
+    CliUnrecognizedDate,
+    CliUnsupportedFormat,
+    CliNotAFolder,
+    CliSkipped,
+    CliUnreadableFolder,
+    CliUsage,
+    UnreadableArchive,
+    MissingPackagePart,
+    EncryptedDocument,
+    LegacyBinaryDocument,
+    UnsupportedOpenDocument,
+    NoMainDocumentPart,
+    JsonParseError,
+    JsonUnknownFields,
+    JsonFieldSuggestion,
+    JsonSchemaVersion,
+    JsonInvalidDate,
+    JsonInvalidCount,
+    JsonInvalidDocSecurity,
+    JsonInvalidFlag,
+}
+
+// Calendar column headers, Monday first
+pub fn weekdays(language: Language) -> [&'static str; 7] {
+    match language {
+        Language::English => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+        Language::Chinese => ["一", "二", "三", "四", "五", "六", "日"],
+    }
+}
+
+pub fn tr(language: Language, key: Text) -> &'static str {
+    match language {
+        Language::English => english(key),
+        Language::Chinese => chinese(key),
+    }
+}
+
+fn english(key: Text) -> &'static str {
+    match key {
+        Text::WindowTitle => "DOCX Metadata Editor",
+        Text::Welcome => "Select a Word (.docx, .docm, .dotx, .dotm), .xlsx, .pptx or .odt file to get started",
+        Text::OpeningFileDialog => "Opening file dialog...",
+        Text::LoadingFrom => "Loading metadata from {}...",
+        Text::ScanningArchive => "Scanning archive...",
+        Text::ReadingProperties => "Reading document properties...",
+        Text::SelectionCancelled => "File selection cancelled.",
+        Text::UnsupportedFileDropped => "Only Word (.docx, .docm, .dotx, .dotm), .xlsx and .pptx files are supported: {}",
+        Text::FileLoaded => "File loaded successfully.",
+        Text::CorePropertiesMissing => {
+            "File loaded, but it has no docProps/core.xml. Saving will create one from the values entered."
+        }
+        Text::AppPropertiesMissing => {
+            "File loaded, but it has no docProps/app.xml. Saving will create one from the values entered."
+        }
+        Text::AppXmlExisting => "app.xml: editing the document's own",
+        Text::AppXmlNew => "app.xml: not in the document; saving creates a new one",
+        Text::CreatingAppXml => "Creating new app.xml",
+        Text::PropertyPartsMissing => {
+            "File loaded, but it has no docProps folder. Saving will create core.xml and app.xml and register them in the package."
+        }
+        Text::DocumentSummary => {
+            "{} KB, {} zip entries; core.xml: {}, app.xml: {}, custom.xml: {}, thumbnail: {}"
+        }
+        Text::OpenDocumentSummary => "{} KB, {} zip entries; meta.xml: {}",
+        Text::MetaPartMissing => {
+            "File loaded, but it has no meta.xml. Saving will create one and list it in the manifest."
+        }
+        Text::OpenDocumentLoaded => {
+            "File loaded. meta.xml only holds the dates, revision, description, keywords, language, application, editing time and counts; other fields are not saved."
+        }
+        Text::PartsListMismatch => {
+            "app.xml is inconsistent: the HeadingPairs counts add up to {}, but TitlesOfParts lists {}"
+        }
+        Text::Yes => "yes",
+        Text::No => "no",
+        Text::SignedDocumentWarning => {
+            "This document is digitally signed. Saving any change will invalidate its signature."
+        }
+        Text::ProceedAnyway => "Edit anyway",
+        Text::Error => "Error: {}",
+        Text::Saving => "Saving changes...",
+        Text::NoFileToSave => "No file selected to save.",
+        Text::FileSaved => "File saved successfully!",
+        Text::ReloadAfterSaveFailed => "Saved {}, but reading it back failed: {}",
+        Text::SaveError => "Error while saving file: {}",
+        Text::SaveProgress => "{}/{} entries, {}/{} KB",
+        Text::SaveCancelled => "Save cancelled; the file was left unchanged.",
+        Text::Cancel => "Cancel",
+        Text::Log => "Log",
+        Text::ClearLog => "Clear log",
+        Text::NoFileSelected => "No file selected",
+        Text::InvalidPath => "Invalid path",
+        Text::SelectFile => "Select document",
+        Text::SaveChanges => "Save changes",
+        Text::SaveAs => "Save as...",
+        Text::TouchAndSave => "Touch and save",
+        Text::RevealInFolder => "Show in folder",
+        Text::FileNoLongerExists => "The file no longer exists: {}",
+        Text::Revert => "Revert",
+        Text::RevertPrompt => "Discard your unsaved changes and reload the file from disk?",
+        Text::KeepEditing => "Keep editing",
+        Text::UnsavedChanges => "You have unsaved changes.",
+        Text::SaveAndClose => "Save and close",
+        Text::CloseWithoutSaving => "Close without saving",
+        Text::FileSavedAs => "Saved an edited copy as {}",
+        Text::AppPropertiesRebuilt => {
+            "{}: docProps/app.xml had an unexpected structure and was rebuilt from the entered values."
+        }
+        Text::SelectFolder => "Apply to folder...",
+        Text::ReadOnly => "Read-only",
+        Text::OpeningFolderDialog => "Opening folder dialog...",
+        Text::FolderSelectionCancelled => "Folder selection cancelled.",
+        Text::NoDocumentsInFolder => "No Word (.docx, .docm, .dotx, .dotm), .xlsx or .pptx files found in {}.",
+        Text::BatchProgress => "{}/{} done",
+        Text::BatchReview => "Choose which documents in {} to save, and in what order.",
+        Text::BatchSelected => "{} of {} documents selected",
+        Text::SaveSelected => "Save selected",
+        Text::SelectAll => "Select all",
+        Text::SelectNone => "Select none",
+        Text::BatchFileSucceeded => "OK: {}",
+        Text::BatchFileFailed => "FAILED: {} ({})",
+        Text::BatchFinished => "Batch finished: {} succeeded, {} failed.",
+        Text::ExportJson => "Export JSON",
+        Text::JsonExported => "Metadata exported to {}",
+        Text::ImportJson => "Import JSON",
+        Text::JsonImported => "Metadata imported. Click save to apply it.",
+        Text::ImportPreview => "Some fields already have values. The import will change:",
+        Text::MergeImport => "Only import fields the JSON has values for",
+        Text::ApplyImport => "Apply import",
+        Text::JsonFilter => "JSON metadata",
+        Text::ExportCsv => "Export CSV report",
+        Text::CsvFilter => "CSV report",
+        Text::CsvExported => "Report exported to {}",
+        Text::CsvRowSkipped => "Left out of the report: {}",
+        Text::PickDate => "Calendar",
+        Text::Anonymize => "Anonymize",
+        Text::ResetDatesOnAnonymize => "Also reset created/modified to",
+        Text::AnonymizeCleared => "Cleared {}; save to apply.",
+        Text::StripEditingHistory => "Strip editing history",
+        Text::EditingHistoryStripped => {
+            "Cleared TotalTime (was \"{}\", now 0) and cp:lastModifiedBy (was \"{}\"); save to apply."
+        }
+        Text::RemoveCustomProperties => "Remove custom properties",
+        Text::RemoveCustomPropertiesPrompt => {
+            "Delete every custom property (docProps/custom.xml) on the next save? This cannot be undone once saved."
+        }
+        Text::CustomPropertiesRemoved => "Custom properties will be removed; save to apply.",
+        Text::RemoveThumbnail => "Remove thumbnail",
+        Text::RemoveThumbnailPrompt => {
+            "Delete the embedded preview image (docProps/thumbnail) on the next save? This cannot be undone once saved."
+        }
+        Text::ThumbnailRemoved => "The thumbnail will be removed; save to apply.",
+        Text::AnonymizeDatesReset => "Created and modified reset to {}.",
+        Text::InvalidNeutralTimestamp => "Dates left unchanged: invalid timestamp \"{}\".",
+        Text::ShiftBy => "Shift dates by:",
+        Text::DateShiftPlaceholder => "e.g. +30d or -12h",
+        Text::ShiftDates => "Shift dates",
+        Text::CopyCreatedToModified => "Created → Modified",
+        Text::CopyModifiedToCreated => "Modified → Created",
+        Text::DatesShifted => "Shifted {} date(s); empty or invalid dates were skipped.",
+        Text::InvalidDateShift => "Invalid offset \"{}\". Use e.g. +30d, -7d or 12h.",
+        Text::VerifyAfterSave => "Verify after saving",
+        Text::ConfirmBeforeSave => "Confirm before overwriting",
+        Text::ConfirmSave => "Confirm",
+        Text::ConfirmSavePrompt => "Saving will overwrite {} with these changes:",
+        Text::ConfirmSaveHint => "Review the changes, then press Confirm to save.",
+        Text::NoFieldChanges => "(no field changes)",
+        Text::DryRun => "Dry run (only report changes)",
+        Text::BlockDateOrder => "Refuse to save dates out of order",
+        Text::CheckFutureDates => "Check for future dates",
+        Text::BlockFutureDates => "Refuse to save them",
+        Text::FutureDateTolerance => "Tolerance (minutes):",
+        Text::FutureDateSaved => "Warning: {} is in the future in {}.",
+        Text::IndentXml => "Indent core.xml and app.xml (reformats the whole part)",
+        Text::LfLineEndings => "Use LF line endings in core.xml and app.xml",
+        Text::StampPartsModified => "Date the property parts' zip entries with the modified date",
+        Text::ReproducibleZip => "Reproducible archive (fixed entry dates and attributes)",
+        Text::Compression => "Compression of rewritten parts:",
+        Text::CompressionNormal => "Normal",
+        Text::CompressionStore => "Store",
+        Text::CompressionFast => "Fast",
+        Text::CompressionBest => "Best",
+        Text::DateProfile => "Write dates as:",
+        Text::DateProfileWord => "Word (UTC, whole seconds)",
+        Text::DateProfileStrictWord => "Word, refusing other forms",
+        Text::DateProfileAsEntered => "As entered",
+        Text::ModifiedBeforeCreated => "Warning: the modified date is earlier than the created date.",
+        Text::LastPrintedBeforeCreated => {
+            "Warning: the last printed date is earlier than the created date."
+        }
+        Text::DryRunFinished => "Dry run: {} field(s) would change; nothing was written. See the log.",
+        Text::Validate => "Validate",
+        Text::ValidationPassed => "Validation passed: the document and its values can be saved.",
+        Text::ValidationFailed => "Validation found {} problem(s); nothing was written.",
+        Text::ChangeAdded => "added",
+        Text::ChangeChanged => "changed",
+        Text::ChangeRemoved => "removed",
+        Text::SyncFileTimes => "Set the file's modified time to match",
+        Text::SyncCreatedTime => "Also set its creation time (Windows/macOS)",
+        Text::CopyFrom => "Copy from...",
+        Text::PasteInto => "Paste",
+        Text::CopiedFrom => "Holding metadata from {}",
+        Text::MetadataCopied => "Copied metadata from {}. Choose the fields and click paste.",
+        Text::MetadataPasted => "Pasted the selected fields; save to apply.",
+        Text::RecentFiles => "Recent files:",
+        Text::DarkTheme => "Dark theme",
+        Text::LightTheme => "Light theme",
+        Text::ShowXml => "Show XML",
+        Text::HideXml => "Hide XML",
+        Text::ShowXmlDiff => "Compare with pending changes",
+        Text::CompareDocuments => "Compare two documents",
+        Text::HideComparison => "Hide comparison",
+        Text::Settings => "Settings",
+        Text::HideSettings => "Hide settings",
+        Text::HighContrast => "High contrast",
+        Text::LocalTimeDisplay => "Show dates in local time",
+        Text::UiScale => "Text and interface size:",
+        Text::ChooseComparedFile => "Choose document {}...",
+        Text::ComparisonDifferences => "{} of {} fields differ.",
+        Text::XmlBefore => "On disk",
+        Text::XmlAfter => "After save",
+        Text::PartMissing => "(not present in this document)",
+        Text::Language => "Language:",
+        Text::CreatedLabel => "Created:",
+        Text::ModifiedLabel => "Modified:",
+        Text::LastPrintedLabel => "Last printed:",
+        Text::RevisionLabel => "Revision:",
+        Text::ContentStatusLabel => "Status:",
+        Text::DescriptionLabel => "Description:",
+        Text::KeywordsLabel => "Keywords:",
+        Text::AddKeyword => "Add",
+        Text::DocumentLanguageLabel => "Language:",
+        Text::CategoryLabel => "Category:",
+        Text::VersionLabel => "Version:",
+        Text::IdentifierLabel => "Identifier:",
+        Text::TemplateLabel => "Template:",
+        Text::ApplicationLabel => "Application:",
+        Text::AppVersionLabel => "App version:",
+        Text::CompanyLabel => "Company:",
+        Text::ManagerLabel => "Manager:",
+        Text::TotalTimeLabel => "Editing time:",
+        Text::PagesLabel => "Pages:",
+        Text::WordsLabel => "Words:",
+        Text::CharactersLabel => "Characters:",
+        Text::DocSecurityLabel => "Protection:",
+        Text::ScaleCropLabel => "Scale thumbnail to fit:",
+        Text::LinksUpToDateLabel => "Links up to date:",
+        Text::SharedDocLabel => "Shared document:",
+        Text::CreatedPlaceholder => "e.g. 2023-01-01T12:00:00Z",
+        Text::ModifiedPlaceholder => "e.g. 2023-01-01T13:00:00Z",
+        Text::LastPrintedPlaceholder => "e.g. 2023-01-01T14:00:00Z",
+        Text::RevisionPlaceholder => "e.g. 3",
+        Text::ContentStatusPlaceholder => "e.g. Draft, Final",
+        Text::InvalidNumber => "Must be a whole number.",
+        Text::InvalidDocSecurity => {
+            "Must be 0 (none), 1 (password), 2 (read-only recommended), 4 (read-only enforced) or up to 8 (locked for annotation)."
+        }
+        Text::KeywordsPlaceholder => "Add keywords, e.g. budget, draft",
+        Text::DocumentLanguagePlaceholder => "e.g. en-US",
+        Text::CommonLanguages => "Common...",
+        Text::CategoryPlaceholder => "e.g. Report",
+        Text::VersionPlaceholder => "e.g. 1.0",
+        Text::IdentifierPlaceholder => "e.g. an ISBN or DOI",
+        Text::TemplatePlaceholder => "e.g. Normal.dotm",
+        Text::ApplicationPlaceholder => "e.g. Microsoft Office Word",
+        Text::AppVersionPlaceholder => "e.g. 16.0000",
+        Text::CompanyPlaceholder => "e.g. Contoso Ltd.",
+        Text::ManagerPlaceholder => "e.g. Jane Doe",
+        Text::TotalTimePlaceholder => "minutes, e.g. 0",
+        Text::CountPlaceholder => "e.g. 0",
+        Text::DocSecurityPlaceholder => "0 to 8, e.g. 0",
+        Text::DocumentFilter => "Office documents",
+        Text::WordDocument => "Word document",
+        Text::ExcelWorkbook => "Excel workbook",
+        Text::PowerPointPresentation => "PowerPoint presentation",
+        Text::OdtDocument => "OpenDocument text",
+        Text::XmlError => "{}: XML error: {}",
+        Text::XmlErrorAt => "{}: XML error at line {}, column {}: {}",
+        Text::UndecodablePart => "{}: cannot be decoded as {}.",
+        Text::DateParseError => "'{}' is not a valid date: \"{}\". Use YYYY-MM-DD, YYYY-MM-DD HH:MM or ISO 8601 (e.g. YYYY-MM-DDTHH:MM:SSZ).",
+        Text::NotWordDate => "'{}' is not in the W3CDTF form Word uses: \"{}\". In strict mode dates must be written as YYYY-MM-DDThh:mm:ssZ (UTC, to the second, e.g. 2024-01-01T09:30:00Z).",
+        Text::CountParseError => "'{}' must be a non-negative integer: \"{}\".",
+        Text::FlagParseError => "'{}' must be true or false: \"{}\".",
+        Text::DocSecurityParseError => "'{}' must be an integer from 0 to 8: \"{}\".",
+        Text::AppVersionParseError => "'{}' must have the form XX.YYYY: \"{}\".",
+        Text::DatesOutOfOrder => "Dates out of order: {}.",
+        Text::ModifiedBeforeCreatedIssue => "the modified date is earlier than the created date",
+        Text::LastPrintedBeforeCreatedIssue => "the last printed date is earlier than the created date",
+        Text::FutureDatesRefused => "Dates later than now: {}.",
+        Text::PartModified => "Verification after saving failed: part {} was modified unexpectedly.",
+        Text::ReplaceOriginalFailed => "Could not replace the original file",
+        Text::WriteNewFileFailed => "Could not write the new file",
+        Text::CreateBackupFailed => "Could not create a backup",
+        Text::CrossVolumeCopyFailed => "{} (copying across volumes failed too)",
+        Text::RemoveTempFileFailed => "Copied, but could not remove the temporary file",
+        Text::SetFileTimesFailed => "The file was saved, but its times could not be set",
+        Text::VerifyRemovedNew => "Verification after saving failed; the new file was deleted",
+        Text::VerifyRestored => "Verification after saving failed; the original file was restored",
+        Text::VerifyRestoreFailed => "Verification after saving failed ({}), and the backup could not be restored",
+        Text::CliMissingValue => "{} is missing its value",
+        Text::CliUnrecognizedDate => "Unrecognized date: {}",
+        Text::CliUnsupportedFormat => "Unsupported output format: {}",
+        Text::CliNotAFolder => "{}: not a folder",
+        Text::CliSkipped => "Warning: skipping {}: {}",
+        Text::CliUnreadableFolder => "Warning: cannot read {}: {}",
+        Text::CliUsage => "Usage: docx-meta-update --read <file> [--format json]\n       docx-meta-update --scan <folder> [--older-than <date>] [--author <text>] [--format csv|json]",
+        Text::UnreadableArchive => "This does not appear to be an Office document (the archive cannot be read: {}).",
+        Text::MissingPackagePart => "This does not appear to be an Office document ({} is missing).",
+        Text::EncryptedDocument => "This document is encrypted with a password; encrypted documents cannot be read or edited yet. Remove the password in Office first.",
+        Text::LegacyBinaryDocument => "This is a legacy binary Office document (.doc, .xls or .ppt), which is not supported. Save it in the newer format first.",
+        Text::UnsupportedOpenDocument => "Only OpenDocument text (.odt) is supported, not {}.",
+        Text::NoMainDocumentPart => "This does not appear to be a Word, Excel or PowerPoint document (no main document part found).",
+        Text::JsonParseError => "JSON parse error: {}",
+        Text::JsonUnknownFields => "Unrecognized fields in the JSON: {}",
+        Text::JsonFieldSuggestion => "'{}' (did you mean '{}'?)",
+        Text::JsonSchemaVersion => "Unsupported JSON schema version: {}",
+        Text::JsonInvalidDate => "Invalid date for '{}' in the JSON: {}",
+        Text::JsonInvalidCount => "'{}' in the JSON must be a non-negative integer: {}",
+        Text::JsonInvalidDocSecurity => "'{}' in the JSON must be an integer from 0 to 8: {}",
+        Text::JsonInvalidFlag => "'{}' in the JSON must be true or false: {}",
+    }
+}
+
+fn chinese(key: Text) -> &'static str {
+    match key {
+        Text::WindowTitle => "DOCX 元数据编辑器",
+        Text::Welcome => "请选择一个 Word (.docx、.docm、.dotx、.dotm)、.xlsx、.pptx 或 .odt 文件开始",
+        Text::OpeningFileDialog => "正在打开文件对话框...",
+        Text::LoadingFrom => "正在从 {} 加载元数据...",
+        Text::ScanningArchive => "正在扫描压缩包...",
+        Text::ReadingProperties => "正在读取文档属性...",
+        Text::SelectionCancelled => "文件选择已取消.",
+        Text::UnsupportedFileDropped => {
+            "仅支持 Word (.docx、.docm、.dotx、.dotm)、.xlsx 和 .pptx 文件: {}"
+        }
+        Text::FileLoaded => "文件加载成功.",
+        Text::CorePropertiesMissing => {
+            "文件已加载, 但缺少 docProps/core.xml. 保存时将用输入的值创建它."
+        }
+        Text::AppPropertiesMissing => {
+            "文件已加载, 但缺少 docProps/app.xml. 保存时将用输入的值创建它."
+        }
+        Text::AppXmlExisting => "app.xml: 编辑文档原有的",
+        Text::AppXmlNew => "app.xml: 文档中没有; 保存时将新建",
+        Text::CreatingAppXml => "将新建 app.xml",
+        Text::PropertyPartsMissing => {
+            "文件已加载, 但没有 docProps 文件夹. 保存时将创建 core.xml 和 app.xml 并在包中注册."
+        }
+        Text::DocumentSummary => {
+            "{} KB, {} 个压缩条目; core.xml: {}, app.xml: {}, custom.xml: {}, 缩略图: {}"
+        }
+        Text::OpenDocumentSummary => "{} KB, {} 个压缩条目; meta.xml: {}",
+        Text::MetaPartMissing => "文件已加载, 但缺少 meta.xml. 保存时将创建它并在清单中登记.",
+        Text::OpenDocumentLoaded => {
+            "文件已加载. meta.xml 只包含日期、修订号、描述、关键词、语言、应用程序、编辑时间和统计数; 其他字段不会保存."
+        }
+        Text::PartsListMismatch => {
+            "app.xml 不一致: HeadingPairs 计数共 {} 项, 但 TitlesOfParts 列出 {} 项"
+        }
+        Text::Yes => "有",
+        Text::No => "无",
+        Text::SignedDocumentWarning => "此文档带有数字签名. 保存任何更改都会使签名失效.",
+        Text::ProceedAnyway => "仍然编辑",
+        Text::Error => "错误: {}",
+        Text::Saving => "正在保存更改...",
+        Text::NoFileToSave => "未选择要保存的文件.",
+        Text::FileSaved => "文件保存成功!",
+        Text::ReloadAfterSaveFailed => "已保存 {}，但重新读取失败: {}",
+        Text::SaveError => "保存文件时出错: {}",
+        Text::SaveProgress => "{}/{} 个条目, {}/{} KB",
+        Text::SaveCancelled => "保存已取消; 文件未被修改.",
+        Text::Cancel => "取消",
+        Text::Log => "日志",
+        Text::ClearLog => "清空日志",
+        Text::NoFileSelected => "未选择文件",
+        Text::InvalidPath => "无效路径",
+        Text::SelectFile => "选择文档",
+        Text::SaveChanges => "保存更改",
+        Text::SaveAs => "另存为...",
+        Text::TouchAndSave => "更新修改时间并保存",
+        Text::RevealInFolder => "在文件夹中显示",
+        Text::FileNoLongerExists => "文件已不存在: {}",
+        Text::Revert => "还原",
+        Text::RevertPrompt => "放弃未保存的更改并从磁盘重新加载文件?",
+        Text::KeepEditing => "继续编辑",
+        Text::UnsavedChanges => "您有未保存的更改.",
+        Text::SaveAndClose => "保存并关闭",
+        Text::CloseWithoutSaving => "不保存直接关闭",
+        Text::FileSavedAs => "已将修改后的副本保存为 {}",
+        Text::AppPropertiesRebuilt => "{}: docProps/app.xml 结构异常, 已根据输入的值重新生成.",
+        Text::SelectFolder => "应用到文件夹...",
+        Text::ReadOnly => "只读",
+        Text::OpeningFolderDialog => "正在打开文件夹对话框...",
+        Text::FolderSelectionCancelled => "文件夹选择已取消.",
+        Text::NoDocumentsInFolder => {
+            "在 {} 中没有找到 Word (.docx、.docm、.dotx、.dotm)、.xlsx 或 .pptx 文件."
+        }
+        Text::BatchProgress => "已完成 {}/{}",
+        Text::BatchReview => "选择要保存的 {} 中的文档及其顺序.",
+        Text::BatchSelected => "已选择 {} / {} 个文档",
+        Text::SaveSelected => "保存所选",
+        Text::SelectAll => "全选",
+        Text::SelectNone => "全不选",
+        Text::BatchFileSucceeded => "成功: {}",
+        Text::BatchFileFailed => "失败: {} ({})",
+        Text::BatchFinished => "批量处理完成: {} 个成功, {} 个失败.",
+        Text::ExportJson => "导出 JSON",
+        Text::JsonExported => "元数据已导出到 {}",
+        Text::ImportJson => "导入 JSON",
+        Text::JsonImported => "元数据已导入. 点击保存以应用.",
+        Text::ImportPreview => "部分字段已有值. 导入将更改:",
+        Text::MergeImport => "仅导入 JSON 中有值的字段",
+        Text::ApplyImport => "应用导入",
+        Text::JsonFilter => "JSON 元数据",
+        Text::ExportCsv => "导出 CSV 报告",
+        Text::CsvFilter => "CSV 报告",
+        Text::CsvExported => "报告已导出到 {}",
+        Text::CsvRowSkipped => "未计入报告: {}",
+        Text::PickDate => "日历",
+        Text::Anonymize => "匿名化",
+        Text::ResetDatesOnAnonymize => "同时将创建/修改日期重置为",
+        Text::AnonymizeCleared => "已清除 {}; 保存后生效.",
+        Text::StripEditingHistory => "清除编辑历史",
+        Text::EditingHistoryStripped => {
+            "已清除 TotalTime (原为 \"{}\", 现为 0) 和 cp:lastModifiedBy (原为 \"{}\"); 保存后生效."
+        }
+        Text::RemoveCustomProperties => "删除自定义属性",
+        Text::RemoveCustomPropertiesPrompt => {
+            "在下次保存时删除全部自定义属性 (docProps/custom.xml)? 保存后无法恢复."
+        }
+        Text::CustomPropertiesRemoved => "将删除自定义属性; 保存后生效.",
+        Text::RemoveThumbnail => "删除缩略图",
+        Text::RemoveThumbnailPrompt => {
+            "在下次保存时删除内嵌的预览图 (docProps/thumbnail)? 保存后无法恢复."
+        }
+        Text::ThumbnailRemoved => "将删除缩略图; 保存后生效.",
+        Text::AnonymizeDatesReset => "创建和修改日期已重置为 {}.",
+        Text::InvalidNeutralTimestamp => "日期未更改: 时间戳 \"{}\" 无效.",
+        Text::ShiftBy => "日期偏移:",
+        Text::DateShiftPlaceholder => "例如, +30d 或 -12h",
+        Text::ShiftDates => "偏移日期",
+        Text::CopyCreatedToModified => "创建 → 修改",
+        Text::CopyModifiedToCreated => "修改 → 创建",
+        Text::DatesShifted => "已偏移 {} 个日期; 空的或无效的日期已跳过.",
+        Text::InvalidDateShift => "无效的偏移量 \"{}\". 请使用例如 +30d、-7d 或 12h.",
+        Text::VerifyAfterSave => "保存后校验",
+        Text::ConfirmBeforeSave => "覆盖前确认",
+        Text::ConfirmSave => "确认",
+        Text::ConfirmSavePrompt => "保存将以下列更改覆盖 {}:",
+        Text::ConfirmSaveHint => "请检查更改, 然后按 \"确认\" 保存.",
+        Text::NoFieldChanges => "(没有字段更改)",
+        Text::DryRun => "试运行 (仅报告更改)",
+        Text::BlockDateOrder => "日期顺序不合理时拒绝保存",
+        Text::CheckFutureDates => "检查未来日期",
+        Text::BlockFutureDates => "拒绝保存",
+        Text::FutureDateTolerance => "容差 (分钟):",
+        Text::FutureDateSaved => "警告: {} 晚于当前时间 ({}).",
+        Text::IndentXml => "缩进 core.xml 和 app.xml (会重排整个部件)",
+        Text::LfLineEndings => "core.xml 和 app.xml 使用 LF 换行",
+        Text::StampPartsModified => "属性部件的压缩条目时间使用修改日期",
+        Text::ReproducibleZip => "可复现的压缩包 (固定条目时间和属性)",
+        Text::Compression => "重写部件的压缩方式:",
+        Text::CompressionNormal => "标准",
+        Text::CompressionStore => "不压缩",
+        Text::CompressionFast => "最快",
+        Text::CompressionBest => "最佳",
+        Text::DateProfile => "日期写入格式:",
+        Text::DateProfileWord => "Word 格式 (UTC, 精确到秒)",
+        Text::DateProfileStrictWord => "Word 格式, 拒绝其他写法",
+        Text::DateProfileAsEntered => "按输入保留",
+        Text::ModifiedBeforeCreated => "警告: 修改日期早于创建日期.",
+        Text::LastPrintedBeforeCreated => "警告: 最后打印日期早于创建日期.",
+        Text::DryRunFinished => "试运行: 将有 {} 个字段更改; 未写入任何内容. 详见日志.",
+        Text::Validate => "验证",
+        Text::ValidationPassed => "验证通过: 文档及其值均可保存.",
+        Text::ValidationFailed => "验证发现 {} 个问题; 未写入任何内容.",
+        Text::ChangeAdded => "新增",
+        Text::ChangeChanged => "修改",
+        Text::ChangeRemoved => "移除",
+        Text::SyncFileTimes => "将文件的修改时间设为与元数据一致",
+        Text::SyncCreatedTime => "同时设置创建时间 (Windows/macOS)",
+        Text::CopyFrom => "从文件复制...",
+        Text::PasteInto => "粘贴",
+        Text::CopiedFrom => "已复制 {} 的元数据",
+        Text::MetadataCopied => "已复制 {} 的元数据. 选择字段后点击粘贴.",
+        Text::MetadataPasted => "已粘贴所选字段; 保存后生效.",
+        Text::RecentFiles => "最近的文件:",
+        Text::DarkTheme => "深色主题",
+        Text::LightTheme => "浅色主题",
+        Text::ShowXml => "显示 XML",
+        Text::HideXml => "隐藏 XML",
+        Text::ShowXmlDiff => "与待保存的更改对比",
+        Text::CompareDocuments => "比较两个文档",
+        Text::HideComparison => "隐藏比较",
+        Text::Settings => "设置",
+        Text::HideSettings => "隐藏设置",
+        Text::HighContrast => "高对比度",
+        Text::LocalTimeDisplay => "以本地时间显示日期",
+        Text::UiScale => "文字与界面大小:",
+        Text::ChooseComparedFile => "选择文档 {}...",
+        Text::ComparisonDifferences => "{} 个字段不同 (共 {} 个).",
+        Text::XmlBefore => "磁盘上",
+        Text::XmlAfter => "保存后",
+        Text::PartMissing => "(此文档中不存在)",
+        Text::Language => "语言:",
+        Text::CreatedLabel => "创建日期:",
+        Text::ModifiedLabel => "修改日期:",
+        Text::LastPrintedLabel => "最后打印:",
+        Text::RevisionLabel => "修订号:",
+        Text::ContentStatusLabel => "状态:",
+        Text::DescriptionLabel => "描述:",
+        Text::KeywordsLabel => "关键词:",
+        Text::AddKeyword => "添加",
+        Text::DocumentLanguageLabel => "语言:",
+        Text::CategoryLabel => "类别:",
+        Text::VersionLabel => "版本:",
+        Text::IdentifierLabel => "标识符:",
+        Text::TemplateLabel => "模板:",
+        Text::ApplicationLabel => "应用程序:",
+        Text::AppVersionLabel => "应用程序版本:",
+        Text::CompanyLabel => "公司:",
+        Text::ManagerLabel => "经理:",
+        Text::TotalTimeLabel => "编辑时间:",
+        Text::PagesLabel => "页数:",
+        Text::WordsLabel => "字数:",
+        Text::CharactersLabel => "字符数:",
+        Text::DocSecurityLabel => "保护级别:",
+        Text::ScaleCropLabel => "缩放缩略图:",
+        Text::LinksUpToDateLabel => "链接已更新:",
+        Text::SharedDocLabel => "共享文档:",
+        Text::CreatedPlaceholder => "例如, 2023-01-01T12:00:00Z",
+        Text::ModifiedPlaceholder => "例如, 2023-01-01T13:00:00Z",
+        Text::LastPrintedPlaceholder => "例如, 2023-01-01T14:00:00Z",
+        Text::RevisionPlaceholder => "例如, 3",
+        Text::ContentStatusPlaceholder => "例如, 草稿, 终稿",
+        Text::InvalidNumber => "必须是整数.",
+        Text::InvalidDocSecurity => {
+            "必须是 0 (无), 1 (密码), 2 (建议只读), 4 (强制只读) 或不超过 8 (仅限批注)."
+        }
+        Text::KeywordsPlaceholder => "添加关键词, 例如 预算, 草稿",
+        Text::DocumentLanguagePlaceholder => "例如, zh-CN",
+        Text::CommonLanguages => "常用...",
+        Text::CategoryPlaceholder => "例如, 报告",
+        Text::VersionPlaceholder => "例如, 1.0",
+        Text::IdentifierPlaceholder => "例如, ISBN 或 DOI",
+        Text::TemplatePlaceholder => "例如, Normal.dotm",
+        Text::ApplicationPlaceholder => "例如, Microsoft Office Word",
+        Text::AppVersionPlaceholder => "例如, 16.0000",
+        Text::CompanyPlaceholder => "例如, Contoso Ltd.",
+        Text::ManagerPlaceholder => "例如, Jane Doe",
+        Text::TotalTimePlaceholder => "分钟, 例如 0",
+        Text::CountPlaceholder => "例如, 0",
+        Text::DocSecurityPlaceholder => "0 到 8, 例如 0",
+        Text::DocumentFilter => "Office 文档",
+        Text::WordDocument => "Word 文档",
+        Text::ExcelWorkbook => "Excel 工作簿",
+        Text::PowerPointPresentation => "PowerPoint 演示文稿",
+        Text::OdtDocument => "OpenDocument 文本",
+        Text::XmlError => "{} XML 错误: {}",
+        Text::XmlErrorAt => "{} XML 错误 (第 {} 行, 第 {} 列): {}",
+        Text::UndecodablePart => "{}: 无法按 {} 编码解码 XML.",
+        Text::DateParseError => "'{}' 格式无效: \"{}\". 请使用 YYYY-MM-DD、YYYY-MM-DD HH:MM 或 ISO 8601 (例如: YYYY-MM-DDTHH:MM:SSZ).",
+        Text::NotWordDate => "'{}' 不符合 Word 使用的 W3CDTF 格式: \"{}\". 严格模式下日期必须写成 YYYY-MM-DDThh:mm:ssZ (UTC, 精确到秒, 例如 2024-01-01T09:30:00Z).",
+        Text::CountParseError => "'{}' 必须是非负整数: \"{}\".",
+        Text::FlagParseError => "'{}' 必须是 true 或 false: \"{}\".",
+        Text::DocSecurityParseError => "'{}' 必须是 0 到 8 之间的整数: \"{}\".",
+        Text::AppVersionParseError => "'{}' 的格式必须为 XX.YYYY: \"{}\".",
+        Text::DatesOutOfOrder => "日期顺序不合理: {}.",
+        Text::ModifiedBeforeCreatedIssue => "修改日期早于创建日期",
+        Text::LastPrintedBeforeCreatedIssue => "最后打印日期早于创建日期",
+        Text::FutureDatesRefused => "日期晚于当前时间: {}.",
+        Text::PartModified => "保存后校验失败: 部件 {} 被意外修改.",
+        Text::ReplaceOriginalFailed => "替换原始文件失败",
+        Text::WriteNewFileFailed => "写入新文件失败",
+        Text::CreateBackupFailed => "无法创建备份",
+        Text::CrossVolumeCopyFailed => "{} (跨卷复制也失败)",
+        Text::RemoveTempFileFailed => "已复制, 但无法删除临时文件",
+        Text::SetFileTimesFailed => "文件已保存, 但无法设置文件时间",
+        Text::VerifyRemovedNew => "保存后校验失败, 已删除新文件",
+        Text::VerifyRestored => "保存后校验失败, 已恢复原文件",
+        Text::VerifyRestoreFailed => "保存后校验失败 ({}), 且无法恢复备份",
+        Text::CliMissingValue => "{} 缺少参数",
+        Text::CliUnrecognizedDate => "无法识别的日期: {}",
+        Text::CliUnsupportedFormat => "不支持的输出格式: {}",
+        Text::CliNotAFolder => "{}: 不是文件夹",
+        Text::CliSkipped => "警告: 跳过 {}: {}",
+        Text::CliUnreadableFolder => "警告: 无法读取 {}: {}",
+        Text::CliUsage => "用法: docx-meta-update --read <文件> [--format json]\n      docx-meta-update --scan <文件夹> [--older-than <日期>] [--author <文本>] [--format csv|json]",
+        Text::UnreadableArchive => "这似乎不是一个 Office 文档 (无法读取压缩包: {}).",
+        Text::MissingPackagePart => "这似乎不是一个 Office 文档 (缺少 {}).",
+        Text::EncryptedDocument => "该文档已使用密码加密, 暂不支持读取或修改加密文档. 请先在 Office 中移除密码.",
+        Text::LegacyBinaryDocument => "这是旧版二进制 Office 文档 (.doc、.xls 或 .ppt), 不受支持. 请先另存为新格式.",
+        Text::UnsupportedOpenDocument => "只支持 OpenDocument 文本 (.odt), 不支持 {}.",
+        Text::NoMainDocumentPart => "这似乎不是一个 Word、Excel 或 PowerPoint 文档 (找不到主文档部件).",
+        Text::JsonParseError => "JSON 解析错误: {}",
+        Text::JsonUnknownFields => "JSON 中有无法识别的字段: {}",
+        Text::JsonFieldSuggestion => "'{}' (是否应为 '{}'?)",
+        Text::JsonSchemaVersion => "不支持的 JSON 架构版本: {}",
+        Text::JsonInvalidDate => "JSON 中 '{}' 的日期格式无效: {}",
+        Text::JsonInvalidCount => "JSON 中 '{}' 必须是非负整数: {}",
+        Text::JsonInvalidDocSecurity => "JSON 中 '{}' 必须是 0 到 8 之间的整数: {}",
+        Text::JsonInvalidFlag => "JSON 中 '{}' 必须是 true 或 false: {}",
+    }
+}
+
+// Substitutes each "{}" in `template` with the next argument, in order.
+pub fn fill(template: &str, args: &[&str]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    let mut args = args.iter();
+    while let Some(pos) = rest.find("{}") {
+        result.push_str(&rest[..pos]);
+        result.push_str(args.next().copied().unwrap_or_default());
+        rest = &rest[pos + 2..];
+    }
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,5007 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::{self, File, FileTimes, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat,
+    Timelike, Utc,
+};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use quick_xml::errors::IllFormedError;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::reader::Reader;
+use quick_xml::writer::Writer;
+use serde::{Deserialize, Serialize};
+use zip::read::ZipFile;
+use zip::result::ZipError;
+use zip::write::{FileOptions, ZipWriter};
+use zip::ZipArchive;
+
+// --- Document CoreMetadata ---
+// The editable fields of a document: dates, revision, status, description,
+// keywords, language, category, version and identifier live in docProps/core.xml; template,
+// application, company, manager, the document statistics, the protection
+// level and the ScaleCrop/LinksUpToDate/SharedDoc flags in docProps/app.xml. Empty strings
+// mean "not set".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CoreMetadata {
+    pub created: String,
+    pub modified: String,
+    pub last_printed: String,
+    pub revision: String, // A non-negative integer when set
+    pub content_status: String,
+    pub description: String,
+    pub keywords: String, // Free text; separators are not interpreted
+    pub language: String, // A BCP-47 tag such as "en-US", though not enforced
+    pub category: String,
+    pub version: String,     // Free text, often like "1.0"
+    pub identifier: String,  // dc:identifier, e.g. an ISBN or DOI
+    pub template: String,    // e.g. "Normal.dotm"
+    pub application: String, // The authoring tool, e.g. "Microsoft Office Word"
+    pub app_version: String, // Its version as "XX.YYYY", e.g. "16.0000"
+    pub company: String,
+    pub manager: String,
+    pub total_time: String, // Editing time in minutes; this and the counts below are integers
+    pub pages: String,
+    pub words: String,
+    pub characters: String,
+    pub doc_security: String, // DocSecurity, a code from 0 to 8 (see is_valid_doc_security)
+    pub scale_crop: String,   // "true" or "false" when set, like the two below
+    pub links_up_to_date: String,
+    pub shared_doc: String,
+}
+
+// --- Errors ---
+// Display gives a plain English message; the GUI and CLI match on the
+// variant to show their own, translated one.
+#[derive(Debug)]
+pub enum DocxMetaError {
+    Io(io::Error),
+    Zip(ZipError),
+    Xml {
+        part: String,
+        message: String,
+        position: Option<XmlPosition>, // Where parsing stopped, when known
+    }, // Malformed part
+    Undecodable {
+        part: String,
+        encoding: &'static str,
+    }, // Not valid text in the encoding it declares
+    DateParse {
+        field: MetadataField,
+        value: String,
+    },
+    NotWordDate {
+        field: MetadataField,
+        value: String,
+    }, // A valid date DateProfile::StrictWord refuses
+    InvalidCount {
+        field: MetadataField,
+        value: String,
+    },
+    InvalidFlag {
+        field: MetadataField,
+        value: String,
+    },
+    InvalidDocSecurity(String),
+    InvalidAppVersion(String),
+    DateOrder(DateOrderIssue),       // SaveOptions::block_date_order
+    FutureDates(Vec<MetadataField>), // FutureDatePolicy::Block
+    PartModified(String),            // verify found a copied entry changed
+    NotADocx(NotADocxReason),
+    MissingPart(String), // A part every package must have
+    Cancelled,
+    // A save step that failed, wrapping the cause
+    Context {
+        step: SaveStep,
+        source: Box<DocxMetaError>,
+    },
+}
+
+// Why a file isn't a package we can edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotADocxReason {
+    Unreadable(String),   // Not a zip archive at all; the zip error
+    Encrypted,            // A password-protected Office document
+    LegacyBinary,         // A .doc, .xls or .ppt
+    OpenDocument(String), // An OpenDocument format other than text; its mimetype
+    NoMainPart,           // Neither a Word, Excel nor PowerPoint main part
+}
+
+// Where in writing the file a save failed, for DocxMetaError::Context
+#[derive(Debug)]
+pub enum SaveStep {
+    ReplaceOriginal,
+    WriteNewFile, // Saving to a path that didn't exist yet
+    CreateBackup,
+    CopyAcrossVolumes(io::Error), // The rename failed so; the copy did too
+    RemoveTempFile,               // Copied, but the temp file is left behind
+    SetFileTimes,                 // Saved; only the file times are off
+    VerifyRemovedNew,             // Verification failed; the new file is deleted
+    VerifyRestored,               // Verification failed; the original is back
+    // Verification failed so, and the backup couldn't be put back either
+    VerifyRestoreFailed(Box<DocxMetaError>),
+}
+
+// A location inside a part's decoded text; line and column count from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XmlPosition {
+    pub line: usize,
+    pub column: usize,
+    pub offset: u64,
+}
+
+impl XmlPosition {
+    fn locate(xml: &str, offset: u64) -> Self {
+        let mut end = (offset as usize).min(xml.len());
+        while !xml.is_char_boundary(end) {
+            end -= 1;
+        }
+        let before = &xml[..end];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        XmlPosition {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
+
+impl DocxMetaError {
+    fn xml(part: &str, error: impl fmt::Display) -> Self {
+        DocxMetaError::Xml {
+            part: part.to_string(),
+            message: error.to_string(),
+            position: None,
+        }
+    }
+
+    // A parse error in `xml`, located where the reader stopped. Syntax
+    // errors carry their own position (the `<` of the broken markup);
+    // others point just past the last event read.
+    fn xml_at(part: &str, error: impl fmt::Display, xml: &str, reader: &Reader<&[u8]>) -> Self {
+        let offset = match reader.error_position() {
+            0 => reader.buffer_position(),
+            offset => offset,
+        };
+        DocxMetaError::Xml {
+            part: part.to_string(),
+            message: error.to_string(),
+            position: Some(XmlPosition::locate(xml, offset)),
+        }
+    }
+
+    fn context(self, step: SaveStep) -> Self {
+        DocxMetaError::Context {
+            step,
+            source: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for DocxMetaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocxMetaError::Io(e) => write!(f, "{}", e),
+            DocxMetaError::Zip(e) => write!(f, "{}", e),
+            DocxMetaError::Xml {
+                part,
+                message,
+                position: None,
+            } => write!(f, "{}: XML error: {}", part, message),
+            DocxMetaError::Xml {
+                part,
+                message,
+                position: Some(position),
+            } => write!(
+                f,
+                "{}: XML error at line {}, column {}: {}",
+                part, position.line, position.column, message
+            ),
+            DocxMetaError::Undecodable { part, encoding } => {
+                write!(f, "{}: not valid {} text", part, encoding)
+            }
+            DocxMetaError::DateParse { field, value } => {
+                write!(f, "{:?}: invalid date \"{}\"", field, value)
+            }
+            DocxMetaError::NotWordDate { field, value } => write!(
+                f,
+                "{:?}: \"{}\" is not in Word's W3CDTF form YYYY-MM-DDThh:mm:ssZ",
+                field, value
+            ),
+            DocxMetaError::InvalidCount { field, value } => {
+                write!(f, "{:?}: not a non-negative integer: \"{}\"", field, value)
+            }
+            DocxMetaError::InvalidFlag { field, value } => {
+                write!(f, "{:?}: not true or false: \"{}\"", field, value)
+            }
+            DocxMetaError::InvalidDocSecurity(value) => {
+                write!(f, "DocSecurity: not an integer from 0 to 8: \"{}\"", value)
+            }
+            DocxMetaError::InvalidAppVersion(value) => {
+                write!(f, "AppVersion: not of the form XX.YYYY: \"{}\"", value)
+            }
+            DocxMetaError::DateOrder(issue) => write!(f, "dates out of order: {:?}", issue),
+            DocxMetaError::FutureDates(fields) => write!(f, "dates in the future: {:?}", fields),
+            DocxMetaError::PartModified(part) => {
+                write!(f, "verification failed: {} was modified", part)
+            }
+            DocxMetaError::NotADocx(NotADocxReason::Unreadable(e)) => {
+                write!(f, "not an Office document: {}", e)
+            }
+            DocxMetaError::NotADocx(NotADocxReason::Encrypted) => {
+                write!(f, "encrypted documents are not supported")
+            }
+            DocxMetaError::NotADocx(NotADocxReason::LegacyBinary) => {
+                write!(f, "legacy binary Office documents are not supported")
+            }
+            DocxMetaError::NotADocx(NotADocxReason::OpenDocument(mimetype)) => {
+                write!(f, "only OpenDocument text is supported, not {}", mimetype)
+            }
+            DocxMetaError::NotADocx(NotADocxReason::NoMainPart) => {
+                write!(f, "not a Word, Excel or PowerPoint document")
+            }
+            DocxMetaError::MissingPart(name) => {
+                write!(f, "not an Office document: {} is missing", name)
+            }
+            DocxMetaError::Cancelled => write!(f, "save cancelled"),
+            DocxMetaError::Context { step, source } => write!(f, "{}: {}", step, source),
+        }
+    }
+}
+
+impl fmt::Display for SaveStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveStep::ReplaceOriginal => write!(f, "could not replace the original file"),
+            SaveStep::WriteNewFile => write!(f, "could not write the new file"),
+            SaveStep::CreateBackup => write!(f, "could not create a backup"),
+            SaveStep::CopyAcrossVolumes(e) => {
+                write!(f, "{} (copying across volumes failed too)", e)
+            }
+            SaveStep::RemoveTempFile => write!(f, "copied, but could not remove the temp file"),
+            SaveStep::SetFileTimes => write!(f, "saved, but could not set the file times"),
+            SaveStep::VerifyRemovedNew => {
+                write!(
+                    f,
+                    "verification failed after saving; the new file was deleted"
+                )
+            }
+            SaveStep::VerifyRestored => {
+                write!(
+                    f,
+                    "verification failed after saving; the original was restored"
+                )
+            }
+            SaveStep::VerifyRestoreFailed(e) => write!(
+                f,
+                "verification failed after saving ({}), and the backup could not be restored",
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DocxMetaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DocxMetaError::Io(e) => Some(e),
+            DocxMetaError::Zip(e) => Some(e),
+            DocxMetaError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DocxMetaError {
+    fn from(e: io::Error) -> Self {
+        DocxMetaError::Io(e)
+    }
+}
+
+impl From<ZipError> for DocxMetaError {
+    fn from(e: ZipError) -> Self {
+        DocxMetaError::Zip(e)
+    }
+}
+
+// Names each editable field, for code that handles them generically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataField {
+    Created,
+    Modified,
+    LastPrinted,
+    Revision,
+    ContentStatus,
+    Description,
+    Keywords,
+    Language,
+    Category,
+    Version,
+    Identifier,
+    Template,
+    Application,
+    AppVersion,
+    Company,
+    Manager,
+    TotalTime,
+    Pages,
+    Words,
+    Characters,
+    DocSecurity,
+    ScaleCrop,
+    LinksUpToDate,
+    SharedDoc,
+}
+
+impl MetadataField {
+    pub const ALL: [MetadataField; 24] = [
+        MetadataField::Created,
+        MetadataField::Modified,
+        MetadataField::LastPrinted,
+        MetadataField::Revision,
+        MetadataField::ContentStatus,
+        MetadataField::Description,
+        MetadataField::Keywords,
+        MetadataField::Language,
+        MetadataField::Category,
+        MetadataField::Version,
+        MetadataField::Identifier,
+        MetadataField::Template,
+        MetadataField::Application,
+        MetadataField::AppVersion,
+        MetadataField::Company,
+        MetadataField::Manager,
+        MetadataField::TotalTime,
+        MetadataField::Pages,
+        MetadataField::Words,
+        MetadataField::Characters,
+        MetadataField::DocSecurity,
+        MetadataField::ScaleCrop,
+        MetadataField::LinksUpToDate,
+        MetadataField::SharedDoc,
+    ];
+}
+
+impl CoreMetadata {
+    pub fn get(&self, field: MetadataField) -> &str {
+        match field {
+            MetadataField::Created => &self.created,
+            MetadataField::Modified => &self.modified,
+            MetadataField::LastPrinted => &self.last_printed,
+            MetadataField::Revision => &self.revision,
+            MetadataField::ContentStatus => &self.content_status,
+            MetadataField::Description => &self.description,
+            MetadataField::Keywords => &self.keywords,
+            MetadataField::Language => &self.language,
+            MetadataField::Category => &self.category,
+            MetadataField::Version => &self.version,
+            MetadataField::Identifier => &self.identifier,
+            MetadataField::Template => &self.template,
+            MetadataField::Application => &self.application,
+            MetadataField::AppVersion => &self.app_version,
+            MetadataField::Company => &self.company,
+            MetadataField::Manager => &self.manager,
+            MetadataField::TotalTime => &self.total_time,
+            MetadataField::Pages => &self.pages,
+            MetadataField::Words => &self.words,
+            MetadataField::Characters => &self.characters,
+            MetadataField::DocSecurity => &self.doc_security,
+            MetadataField::ScaleCrop => &self.scale_crop,
+            MetadataField::LinksUpToDate => &self.links_up_to_date,
+            MetadataField::SharedDoc => &self.shared_doc,
+        }
+    }
+
+    pub fn get_mut(&mut self, field: MetadataField) -> &mut String {
+        match field {
+            MetadataField::Created => &mut self.created,
+            MetadataField::Modified => &mut self.modified,
+            MetadataField::LastPrinted => &mut self.last_printed,
+            MetadataField::Revision => &mut self.revision,
+            MetadataField::ContentStatus => &mut self.content_status,
+            MetadataField::Description => &mut self.description,
+            MetadataField::Keywords => &mut self.keywords,
+            MetadataField::Language => &mut self.language,
+            MetadataField::Category => &mut self.category,
+            MetadataField::Version => &mut self.version,
+            MetadataField::Identifier => &mut self.identifier,
+            MetadataField::Template => &mut self.template,
+            MetadataField::Application => &mut self.application,
+            MetadataField::AppVersion => &mut self.app_version,
+            MetadataField::Company => &mut self.company,
+            MetadataField::Manager => &mut self.manager,
+            MetadataField::TotalTime => &mut self.total_time,
+            MetadataField::Pages => &mut self.pages,
+            MetadataField::Words => &mut self.words,
+            MetadataField::Characters => &mut self.characters,
+            MetadataField::DocSecurity => &mut self.doc_security,
+            MetadataField::ScaleCrop => &mut self.scale_crop,
+            MetadataField::LinksUpToDate => &mut self.links_up_to_date,
+            MetadataField::SharedDoc => &mut self.shared_doc,
+        }
+    }
+}
+
+// --- Document Kinds ---
+// Word, Excel and PowerPoint packages share docProps/core.xml and app.xml;
+// they are told apart by their main part. An OpenDocument text keeps its
+// metadata in meta.xml instead (see the OpenDocument Text section).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Word,
+    Excel,
+    PowerPoint,
+    OpenDocumentText,
+}
+
+impl DocumentKind {
+    pub const ALL: [DocumentKind; 4] = [
+        DocumentKind::Word,
+        DocumentKind::Excel,
+        DocumentKind::PowerPoint,
+        DocumentKind::OpenDocumentText,
+    ];
+
+    pub fn main_part(self) -> &'static str {
+        match self {
+            DocumentKind::Word => "word/document.xml",
+            DocumentKind::Excel => "xl/workbook.xml",
+            DocumentKind::PowerPoint => "ppt/presentation.xml",
+            DocumentKind::OpenDocumentText => "content.xml",
+        }
+    }
+
+    pub fn is_open_document(self) -> bool {
+        self == DocumentKind::OpenDocumentText
+    }
+
+    // What Office writes as <Application>, which an app.xml created from
+    // scratch falls back on. meta.xml leaves meta:generator to the user.
+    pub fn default_application(self) -> Option<&'static str> {
+        match self {
+            DocumentKind::Word => Some("Microsoft Office Word"),
+            DocumentKind::Excel => Some("Microsoft Excel"),
+            DocumentKind::PowerPoint => Some("Microsoft Office PowerPoint"),
+            DocumentKind::OpenDocumentText => None,
+        }
+    }
+
+    // Whether Office writes the app.xml element `name` for this kind. The
+    // page and character counts are Word's; a workbook has no template,
+    // editing time or word count either.
+    pub fn uses_app_element(self, name: &str) -> bool {
+        match self {
+            DocumentKind::Excel => !matches!(
+                name,
+                "Template" | "TotalTime" | "Pages" | "Words" | "Characters"
+            ),
+            DocumentKind::PowerPoint => !matches!(name, "Pages" | "Characters"),
+            DocumentKind::Word | DocumentKind::OpenDocumentText => true,
+        }
+    }
+}
+
+// --- Date Handling ---
+
+// Accepts full RFC 3339, "YYYY-MM-DD HH:MM" and "YYYY-MM-DD" (the latter two
+// taken as UTC, midnight when the time is omitted) and returns the canonical
+// form DateProfile::AsEntered writes. An offset is kept as given, so
+// "2024-01-01T09:00:00+08:00" stays as it is; UTC is written as "...Z".
+pub fn normalize_date(value: &str) -> Option<String> {
+    parse_date_with_offset(value).map(|dt| dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+// The instant a date stands for, whatever offset it was written with
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    parse_date_with_offset(value).map(|dt| dt.with_timezone(&Utc))
+}
+
+// Like parse_date, but in the value's own offset
+pub fn parse_date_with_offset(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        Some(dt)
+    } else if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M") {
+        Some(dt.and_utc().fixed_offset())
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Some(date.and_time(NaiveTime::MIN).and_utc().fixed_offset())
+    } else {
+        None
+    }
+}
+
+// cp:revision and the app.xml statistics must be plain non-negative integers
+pub fn is_valid_count(value: &str) -> bool {
+    value.trim().parse::<u32>().is_ok()
+}
+
+fn normalize_count_field(value: &str, field: MetadataField) -> Result<String, DocxMetaError> {
+    match value.trim() {
+        "" => Ok(String::new()),
+        count if is_valid_count(count) => Ok(count.to_string()),
+        count => Err(DocxMetaError::InvalidCount {
+            field,
+            value: count.to_string(),
+        }),
+    }
+}
+
+// DocSecurity codes: 0 none, 1 password protected, 2 read-only
+// recommended, 4 read-only enforced, 8 locked for annotation. Office
+// documents only the values up to 8.
+pub fn is_valid_doc_security(value: &str) -> bool {
+    value.trim().parse::<u8>().is_ok_and(|code| code <= 8)
+}
+
+fn normalize_doc_security(value: &str) -> Result<String, DocxMetaError> {
+    match value.trim() {
+        "" => Ok(String::new()),
+        code => match code.parse::<u8>() {
+            Ok(level) if level <= 8 => Ok(level.to_string()),
+            _ => Err(DocxMetaError::InvalidDocSecurity(code.to_string())),
+        },
+    }
+}
+
+// cp:keywords is a single string. Office separates keywords with commas or
+// semicolons; the first separator found, with the space after it if any,
+// is the one an edited list is joined back with.
+pub fn keyword_separator(keywords: &str) -> Option<&'static str> {
+    let (index, separator) = keywords
+        .char_indices()
+        .find(|(_, ch)| matches!(ch, ',' | ';'))?;
+    let spaced = keywords[index + 1..].starts_with(' ');
+    Some(match (separator, spaced) {
+        (',', true) => ", ",
+        (',', false) => ",",
+        (_, true) => "; ",
+        _ => ";",
+    })
+}
+
+pub fn split_keywords(keywords: &str) -> Vec<String> {
+    keywords
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// app.xml flags are xsd:boolean, which also allows "1" and "0"
+pub fn is_valid_flag(value: &str) -> bool {
+    matches!(value.trim(), "true" | "false" | "1" | "0")
+}
+
+pub fn is_flag_set(value: &str) -> bool {
+    matches!(value.trim(), "true" | "1")
+}
+
+// Flags are written as "true" or "false", the way Office writes them
+fn normalize_flag_field(value: &str, field: MetadataField) -> Result<String, DocxMetaError> {
+    match value.trim() {
+        "" => Ok(String::new()),
+        flag if is_valid_flag(flag) => Ok(is_flag_set(flag).to_string()),
+        flag => Err(DocxMetaError::InvalidFlag {
+            field,
+            value: flag.to_string(),
+        }),
+    }
+}
+
+// The W3CDTF subset Word itself writes: UTC, whole seconds, e.g.
+// "2024-01-01T01:00:00Z". Some consumers reject any other form.
+const WORD_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+// How a save writes the date fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateProfile {
+    #[default]
+    Word, // Convert to Word's profile, in UTC and dropping fractions of a second
+    StrictWord, // Refuse dates not already written in Word's profile
+    AsEntered,  // Keep the offset and precision a date was entered with
+}
+
+impl DateProfile {
+    pub const ALL: [DateProfile; 3] = [
+        DateProfile::Word,
+        DateProfile::StrictWord,
+        DateProfile::AsEntered,
+    ];
+}
+
+// Any date normalize_date accepts, in Word's profile
+pub fn to_word_date(value: &str) -> Option<String> {
+    parse_date(value).map(|dt| dt.format(WORD_DATE_FORMAT).to_string())
+}
+
+// Exactly "YYYY-MM-DDThh:mm:ssZ", zero-padded (chrono alone would also take
+// "2024-1-1T1:00:00Z")
+pub fn is_word_date(value: &str) -> bool {
+    value.len() == 20 && NaiveDateTime::parse_from_str(value, WORD_DATE_FORMAT).is_ok()
+}
+
+fn normalize_date_field(
+    value: &str,
+    field: MetadataField,
+    profile: DateProfile,
+) -> Result<String, DocxMetaError> {
+    let normalized = match profile {
+        DateProfile::Word => to_word_date(value),
+        DateProfile::AsEntered => normalize_date(value),
+        DateProfile::StrictWord if is_word_date(value.trim()) => Some(value.trim().to_string()),
+        DateProfile::StrictWord if parse_date(value).is_some() => {
+            return Err(DocxMetaError::NotWordDate {
+                field,
+                value: value.trim().to_string(),
+            })
+        }
+        DateProfile::StrictWord => None,
+    };
+    normalized.ok_or_else(|| DocxMetaError::DateParse {
+        field,
+        value: value.to_string(),
+    })
+}
+
+// W3CDTF's reduced precisions "YYYY", "YYYY-MM" and "YYYY-MM-DD", which some
+// generators write for dcterms:created. Checked on the whole value, so
+// "2024-1-5" or a trailing time don't count.
+pub fn is_reduced_date(value: &str) -> bool {
+    let value = value.trim();
+    let digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    match value.split('-').collect::<Vec<_>>()[..] {
+        [year] => digits(year, 4),
+        [year, month] => {
+            digits(year, 4)
+                && digits(month, 2)
+                && month
+                    .parse::<u32>()
+                    .is_ok_and(|month| (1..=12).contains(&month))
+        }
+        [_, _, _] => value.len() == 10 && NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        _ => false,
+    }
+}
+
+// A signed offset such as "+30d", "-12h" or "7" (days when no unit is given)
+pub fn parse_offset(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (amount, hours_per_unit) = match value.strip_suffix(['h', 'H']) {
+        Some(amount) => (amount, 1),
+        None => (value.strip_suffix(['d', 'D']).unwrap_or(value), 24),
+    };
+    let amount = amount.trim();
+    let amount: i64 = amount.strip_prefix('+').unwrap_or(amount).parse().ok()?;
+    Duration::try_hours(amount.checked_mul(hours_per_unit)?)
+}
+
+// Moves a date by `offset`, returning it normalized; None if it doesn't parse
+pub fn shift_date(value: &str, offset: Duration) -> Option<String> {
+    let shifted = parse_date_with_offset(value)?.checked_add_signed(offset)?;
+    Some(shifted.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+// Dates that are each valid but contradict one another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrderIssue {
+    ModifiedBeforeCreated,
+    LastPrintedBeforeCreated,
+}
+
+// Unparseable or empty dates are left to the format validation
+pub fn date_order_issues(metadata: &CoreMetadata) -> Vec<DateOrderIssue> {
+    let Some(created) = parse_date(&metadata.created) else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    if parse_date(&metadata.modified).is_some_and(|modified| modified < created) {
+        issues.push(DateOrderIssue::ModifiedBeforeCreated);
+    }
+    if parse_date(&metadata.last_printed).is_some_and(|printed| printed < created) {
+        issues.push(DateOrderIssue::LastPrintedBeforeCreated);
+    }
+    issues
+}
+
+// --- Reading ---
+
+pub fn load_metadata(path: &Path) -> Result<CoreMetadata, DocxMetaError> {
+    load_metadata_from_reader(File::open(path)?)
+}
+
+// Same as load_metadata, for a package that is already in memory (e.g. a
+// Cursor over an upload) or otherwise not on disk
+pub fn load_metadata_from_reader<R: Read + Seek>(reader: R) -> Result<CoreMetadata, DocxMetaError> {
+    read_metadata(&mut open_archive(reader)?)
+}
+
+fn read_metadata<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<CoreMetadata, DocxMetaError> {
+    let kind = detect_document_kind(archive)?;
+
+    let mut metadata = CoreMetadata::default();
+    if kind.is_open_document() {
+        load_odt_meta(archive, &mut metadata)?;
+    } else {
+        load_core_properties(archive, &mut metadata)?;
+        load_app_properties(archive, &mut metadata)?;
+    }
+    Ok(metadata)
+}
+
+// Some generators leave core.xml out; that loads as all-empty fields and
+// the next save creates the part.
+fn load_core_properties<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    metadata: &mut CoreMetadata,
+) -> Result<(), DocxMetaError> {
+    match read_core_part(archive)? {
+        Some(core_props) => parse_core_properties(&core_props.text, metadata),
+        None => Ok(()),
+    }
+}
+
+fn parse_core_properties(xml: &str, metadata: &mut CoreMetadata) -> Result<(), DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut seen: Vec<Vec<u8>> = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let field = match e.name().as_ref() {
+                    b"dcterms:created" => Some(&mut metadata.created),
+                    b"dcterms:modified" => Some(&mut metadata.modified),
+                    b"cp:lastPrinted" => Some(&mut metadata.last_printed),
+                    b"cp:revision" => Some(&mut metadata.revision),
+                    b"cp:contentStatus" => Some(&mut metadata.content_status),
+                    b"dc:description" => Some(&mut metadata.description),
+                    b"cp:keywords" => Some(&mut metadata.keywords),
+                    b"dc:language" => Some(&mut metadata.language),
+                    b"cp:category" => Some(&mut metadata.category),
+                    b"cp:version" => Some(&mut metadata.version),
+                    b"dc:identifier" => Some(&mut metadata.identifier),
+                    _ => None,
+                };
+                if let Some(field) = field {
+                    let name = e.name().as_ref().to_vec();
+                    let value = read_value(&mut reader)
+                        .map_err(|e| DocxMetaError::xml_at(CORE_PART.name, e, xml, &reader))?;
+                    // A duplicated element keeps its first value, the one
+                    // generate_core_xml replaces
+                    if !seen.contains(&name) {
+                        *field = value;
+                        seen.push(name);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(CORE_PART.name, e, xml, &reader)),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+// --- Authors ---
+// dc:creator and cp:lastModifiedBy are not editable fields (anonymizing is
+// the only way to change them), but reports still want to show them. In
+// meta.xml they are meta:initial-creator and dc:creator.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Authors {
+    pub creator: String,
+    pub last_modified_by: String,
+}
+
+pub fn load_authors(path: &Path) -> Result<Authors, DocxMetaError> {
+    let mut archive = open_document(path)?;
+    if is_open_document_text(&mut archive) {
+        return match read_xml_part(&mut archive, ODT_META_PART)? {
+            Some(meta) => parse_authors(&meta.text, ODT_META_PART, ODT_AUTHOR_ELEMENTS),
+            None => Ok(Authors::default()),
+        };
+    }
+    match read_core_part(&mut archive)? {
+        Some(core_props) => parse_authors(&core_props.text, CORE_PART.name, CORE_AUTHOR_ELEMENTS),
+        None => Ok(Authors::default()),
+    }
+}
+
+// The creator and last author elements, by qualified name
+const CORE_AUTHOR_ELEMENTS: [&str; 2] = ["dc:creator", "cp:lastModifiedBy"];
+
+fn parse_authors(xml: &str, part: &str, elements: [&str; 2]) -> Result<Authors, DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut authors = Authors::default();
+    let mut seen: Vec<Vec<u8>> = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = e.name().as_ref().to_vec();
+                let field = if name == elements[0].as_bytes() {
+                    Some(&mut authors.creator)
+                } else if name == elements[1].as_bytes() {
+                    Some(&mut authors.last_modified_by)
+                } else {
+                    None
+                };
+                if let Some(field) = field {
+                    let value = read_value(&mut reader)
+                        .map_err(|e| DocxMetaError::xml_at(part, e, xml, &reader))?;
+                    // The first of duplicated elements wins, as in parse_core_properties
+                    if !seen.contains(&name) {
+                        *field = value;
+                        seen.push(name);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(part, e, xml, &reader)),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(authors)
+}
+
+// --- CSV Export ---
+// A document's metadata as one row of a report
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRecord {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub authors: Authors,
+    #[serde(flatten)]
+    pub metadata: CoreMetadata,
+}
+
+pub fn load_record(path: &Path) -> Result<MetadataRecord, DocxMetaError> {
+    Ok(MetadataRecord {
+        path: path.to_path_buf(),
+        authors: load_authors(path)?,
+        metadata: load_metadata(path)?,
+    })
+}
+
+// RFC 4180 text: a header naming each column after its element
+// ("dc:creator", "dcterms:created", "Template"...), then a line per record
+// with the fields in MetadataField::ALL order. Fields holding a comma,
+// quote or line break are quoted, with quotes doubled.
+pub fn records_to_csv(records: &[MetadataRecord]) -> String {
+    let mut header = vec!["path", "dc:creator", "cp:lastModifiedBy"];
+    header.extend(MetadataField::ALL.map(|field| {
+        core_element(field)
+            .or(app_element(field))
+            .unwrap_or_default()
+    }));
+    let mut csv = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+    // Writing to memory can't fail, and every field is UTF-8 to begin with
+    let _ = csv.write_record(header);
+    for record in records {
+        let path = record.path.display().to_string();
+        let mut fields = vec![
+            path.as_str(),
+            record.authors.creator.as_str(),
+            record.authors.last_modified_by.as_str(),
+        ];
+        fields.extend(MetadataField::ALL.map(|field| record.metadata.get(field)));
+        let _ = csv.write_record(fields);
+    }
+    String::from_utf8_lossy(&csv.into_inner().unwrap_or_default()).into_owned()
+}
+
+// The text of the element whose start tag was just read, up to its end tag:
+// entity and character references are resolved and CDATA sections
+// unwrapped, so "R&amp;D" loads as "R&D". (read_text would return the raw,
+// still-escaped markup.) Text inside nested elements is included.
+fn read_value(reader: &mut Reader<&[u8]>) -> Result<String, quick_xml::Error> {
+    let mut value = String::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event()? {
+            Event::Text(e) => value.push_str(&e.decode()?),
+            Event::CData(e) => value.push_str(&e.decode()?),
+            Event::GeneralRef(e) => match e.resolve_char_ref()? {
+                Some(ch) => value.push(ch),
+                None => {
+                    let name = e.decode()?;
+                    match resolve_predefined_entity(&name) {
+                        Some(text) => value.push_str(text),
+                        // Only a DTD could define it; keep the reference as written
+                        None => value.push_str(&format!("&{};", name)),
+                    }
+                }
+            },
+            Event::Start(_) => depth += 1,
+            Event::End(_) if depth == 0 => break,
+            Event::End(_) => depth -= 1,
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(value)
+}
+
+// Raw text of a package part, e.g. "docProps/core.xml", for inspection.
+// A part the package doesn't have yields None.
+pub fn read_part(path: &Path, name: &str) -> Result<Option<String>, DocxMetaError> {
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    Ok(read_xml_part(&mut archive, name)?.map(|part| part.text))
+}
+
+// Checks that the package opens and its property parts parse without XML
+// errors.
+pub fn verify_document(path: &Path) -> Result<(), DocxMetaError> {
+    let mut archive = open_document(path)?;
+    let parts = if detect_document_kind(&mut archive)?.is_open_document() {
+        vec![ODT_META_PART.to_string()]
+    } else {
+        let core_name = core_part_name(&mut archive);
+        vec![
+            core_name.unwrap_or(CORE_PART.name.to_string()),
+            APP_PART.name.to_string(),
+        ]
+    };
+    for part in &parts {
+        let Some(xml) = read_xml_part(&mut archive, part)? else {
+            continue;
+        };
+        let mut reader = Reader::from_str(&xml.text);
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(DocxMetaError::xml_at(part, e, &xml.text, &reader)),
+                _ => (),
+            }
+            buf.clear();
+        }
+    }
+    Ok(())
+}
+
+pub fn document_kind(path: &Path) -> Result<DocumentKind, DocxMetaError> {
+    detect_document_kind(&mut open_document(path)?)
+}
+
+// Read-only facts about a package, for an at-a-glance overview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSummary {
+    pub kind: DocumentKind,
+    pub file_size: u64,            // In bytes
+    pub entries: usize,            // Zip entries, directories included
+    pub has_core_properties: bool, // meta.xml, for an OpenDocument text
+    pub has_app_properties: bool,
+    pub has_custom_properties: bool,
+    pub has_thumbnail: bool, // docProps/thumbnail.*
+    pub parts_list_mismatch: Option<PartsListMismatch>,
+}
+
+pub fn document_summary(path: &Path) -> Result<DocumentSummary, DocxMetaError> {
+    let file_size = fs::metadata(path)?.len();
+    let mut archive = open_document(path)?;
+    let parts_list_mismatch = read_xml_part(&mut archive, APP_PART.name)?
+        .and_then(|app_props| check_parts_list(&app_props.text));
+    let kind = detect_document_kind(&mut archive)?;
+    let has_core_properties = if kind.is_open_document() {
+        archive.index_for_name(ODT_META_PART).is_some()
+    } else {
+        core_part_name(&mut archive).is_some()
+    };
+    Ok(DocumentSummary {
+        kind,
+        file_size,
+        entries: archive.len(),
+        has_core_properties,
+        has_app_properties: archive.index_for_name(APP_PART.name).is_some(),
+        has_custom_properties: archive.index_for_name(CUSTOM_PART.name).is_some(),
+        has_thumbnail: !kind.is_open_document() && thumbnail_part(&archive).is_some(),
+        parts_list_mismatch,
+    })
+}
+
+// app.xml's HeadingPairs pairs each category of parts ("Title", "Headings"...)
+// with a count, and TitlesOfParts lists those parts in order, so the counts
+// should add up to the number of titles. Renamed or hand-edited documents
+// sometimes disagree, which Office may reject or misreport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartsListMismatch {
+    pub counted: usize, // The HeadingPairs counts, summed
+    pub titles: usize,  // Entries in TitlesOfParts
+}
+
+// Best-effort: a part that doesn't parse is left to load_metadata to report
+fn check_parts_list(xml: &str) -> Option<PartsListMismatch> {
+    let mut reader = Reader::from_str(xml);
+    let mut in_heading_pairs = false;
+    let mut in_titles = false;
+    let mut found = false;
+    let mut counted = 0usize;
+    let mut titles = 0usize;
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"HeadingPairs" => (in_heading_pairs, found) = (true, true),
+                b"TitlesOfParts" => (in_titles, found) = (true, true),
+                b"i4" if in_heading_pairs => {
+                    let count = read_value(&mut reader).ok()?;
+                    counted += count.trim().parse::<usize>().ok()?;
+                }
+                b"lpstr" | b"lpwstr" if in_titles => titles += 1,
+                _ => {}
+            },
+            Event::Empty(e) if in_titles => {
+                if matches!(e.local_name().as_ref(), b"lpstr" | b"lpwstr") {
+                    titles += 1;
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"HeadingPairs" => in_heading_pairs = false,
+                b"TitlesOfParts" => in_titles = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    (found && counted != titles).then_some(PartsListMismatch { counted, titles })
+}
+
+// Signed packages keep their signatures under _xmlsignatures/, linked from
+// _rels/.rels by a digital-signature origin relationship; an OpenDocument
+// package keeps them in META-INF/documentsignatures.xml. Any edit we save
+// invalidates them.
+pub fn is_signed(path: &Path) -> Result<bool, DocxMetaError> {
+    let mut archive = open_document(path)?;
+    if archive
+        .file_names()
+        .any(|name| name.starts_with("_xmlsignatures/") || name == ODT_SIGNATURES)
+    {
+        return Ok(true);
+    }
+    let Some(rels) = read_xml_part(&mut archive, PACKAGE_RELS)? else {
+        return Ok(false);
+    };
+    let types = attribute_values(rels.text.as_bytes(), b"Relationship", "Type")
+        .map_err(|e| DocxMetaError::xml(PACKAGE_RELS, e))?;
+    Ok(types
+        .iter()
+        .any(|kind| kind.ends_with("/digital-signature/origin")))
+}
+
+// Office saves password-protected documents as an OLE compound file that
+// wraps the encrypted package, so they never open as a zip. The legacy
+// binary formats (.doc, .xls, .ppt) share the container.
+const COMPOUND_FILE_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+fn open_document(path: &Path) -> Result<ZipArchive<File>, DocxMetaError> {
+    open_archive(File::open(path)?)
+}
+
+fn open_archive<R: Read + Seek>(mut reader: R) -> Result<ZipArchive<R>, DocxMetaError> {
+    let mut magic = [0u8; 8];
+    if reader.read_exact(&mut magic).is_ok() && magic == COMPOUND_FILE_MAGIC {
+        return Err(compound_file_error(&mut reader));
+    }
+    reader.seek(SeekFrom::Start(0))?;
+    ZipArchive::new(reader)
+        .map_err(|e| DocxMetaError::NotADocx(NotADocxReason::Unreadable(e.to_string())))
+}
+
+// An encrypted package is stored next to an "EncryptionInfo" stream, whose
+// name appears UTF-16LE encoded in the compound file's directory.
+fn compound_file_error(file: &mut impl Read) -> DocxMetaError {
+    let marker: Vec<u8> = "EncryptionInfo"
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect();
+    let mut bytes = Vec::new();
+    let encrypted = file.read_to_end(&mut bytes).is_ok()
+        && bytes.windows(marker.len()).any(|window| window == marker);
+    DocxMetaError::NotADocx(if encrypted {
+        NotADocxReason::Encrypted
+    } else {
+        NotADocxReason::LegacyBinary
+    })
+}
+
+// A renamed .zip passes ZipArchive::new, so check for the parts every
+// supported package must carry before we ever consider writing to it. An
+// OpenDocument package names its format in its "mimetype" entry instead.
+fn detect_document_kind<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<DocumentKind, DocxMetaError> {
+    if let Some(mimetype) =
+        open_document_mimetype(archive).filter(|mimetype| mimetype.starts_with(ODF_MIMETYPE_PREFIX))
+    {
+        return if mimetype == ODT_MIMETYPE {
+            Ok(DocumentKind::OpenDocumentText)
+        } else {
+            Err(DocxMetaError::NotADocx(NotADocxReason::OpenDocument(
+                mimetype,
+            )))
+        };
+    }
+    if archive.index_for_name(CONTENT_TYPES).is_none() {
+        return Err(DocxMetaError::MissingPart(CONTENT_TYPES.to_string()));
+    }
+    DocumentKind::ALL
+        .into_iter()
+        .filter(|kind| !kind.is_open_document())
+        .find(|kind| archive.index_for_name(kind.main_part()).is_some())
+        .ok_or(DocxMetaError::NotADocx(NotADocxReason::NoMainPart))
+}
+
+// app.xml is optional, so a missing part simply yields empty values.
+fn load_app_properties<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    metadata: &mut CoreMetadata,
+) -> Result<(), DocxMetaError> {
+    match read_xml_part(archive, APP_PART.name)? {
+        Some(app_props) => parse_app_properties(&app_props.text, metadata),
+        None => Ok(()),
+    }
+}
+
+fn parse_app_properties(xml: &str, metadata: &mut CoreMetadata) -> Result<(), DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let field = match e.local_name().as_ref() {
+                    b"Template" => Some(&mut metadata.template),
+                    b"Application" => Some(&mut metadata.application),
+                    b"AppVersion" => Some(&mut metadata.app_version),
+                    b"Company" => Some(&mut metadata.company),
+                    b"Manager" => Some(&mut metadata.manager),
+                    b"TotalTime" => Some(&mut metadata.total_time),
+                    b"Pages" => Some(&mut metadata.pages),
+                    b"Words" => Some(&mut metadata.words),
+                    b"Characters" => Some(&mut metadata.characters),
+                    b"DocSecurity" => Some(&mut metadata.doc_security),
+                    b"ScaleCrop" => Some(&mut metadata.scale_crop),
+                    b"LinksUpToDate" => Some(&mut metadata.links_up_to_date),
+                    b"SharedDoc" => Some(&mut metadata.shared_doc),
+                    _ => None,
+                };
+                if let Some(field) = field {
+                    *field = read_value(&mut reader)
+                        .map_err(|e| DocxMetaError::xml_at(APP_PART.name, e, xml, &reader))?;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(APP_PART.name, e, xml, &reader)),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+// --- Custom Properties ---
+// docProps/custom.xml holds user-defined name/value pairs, each value typed
+// by a single vt: child (vt:lpwstr, vt:i4, vt:bool, vt:filetime...). They
+// are read as plain text and never edited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomProperty {
+    pub name: String,
+    pub value: String,
+}
+
+pub fn load_custom_properties(path: &Path) -> Result<Vec<CustomProperty>, DocxMetaError> {
+    let mut archive = open_document(path)?;
+    match read_xml_part(&mut archive, CUSTOM_PART.name)? {
+        Some(custom_props) => parse_custom_properties(&custom_props.text),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn parse_custom_properties(xml: &str) -> Result<Vec<CustomProperty>, DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut properties = Vec::new();
+    // Set inside a <property>, until its value element is read
+    let mut name: Option<String> = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"property" => {
+                name = e
+                    .try_get_attribute("name")
+                    .ok()
+                    .flatten()
+                    .map(|attr| String::from_utf8_lossy(&attr.value).into_owned());
+            }
+            Ok(Event::Start(_)) => {
+                if let Some(name) = name.take() {
+                    let value = read_value(&mut reader)
+                        .map_err(|e| DocxMetaError::xml_at(CUSTOM_PART.name, e, xml, &reader))?;
+                    properties.push(CustomProperty { name, value });
+                }
+            }
+            Ok(Event::Empty(_)) => {
+                if let Some(name) = name.take() {
+                    properties.push(CustomProperty {
+                        name,
+                        value: String::new(),
+                    });
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(CUSTOM_PART.name, e, xml, &reader)),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(properties)
+}
+
+// --- Transforms ---
+// Scripted edits applied on every save, e.g. uppercasing a field. They run
+// in SaveOptions::transforms order on the edited metadata, before it is
+// validated and the parts are generated.
+
+pub trait MetadataTransform: fmt::Debug + Send + Sync {
+    fn apply(&self, metadata: CoreMetadata) -> CoreMetadata;
+}
+
+// Strips leading and trailing whitespace from every field
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrimWhitespace;
+
+impl MetadataTransform for TrimWhitespace {
+    fn apply(&self, mut metadata: CoreMetadata) -> CoreMetadata {
+        for field in MetadataField::ALL {
+            let value = metadata.get_mut(field);
+            *value = value.trim().to_string();
+        }
+        metadata
+    }
+}
+
+// Rewrites each date that parses in the canonical "...Z" form; anything
+// else is left for validation to report
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeDates;
+
+impl MetadataTransform for NormalizeDates {
+    fn apply(&self, mut metadata: CoreMetadata) -> CoreMetadata {
+        for field in [
+            MetadataField::Created,
+            MetadataField::Modified,
+            MetadataField::LastPrinted,
+        ] {
+            if let Some(date) = normalize_date(metadata.get(field)) {
+                *metadata.get_mut(field) = date;
+            }
+        }
+        metadata
+    }
+}
+
+fn transformed(metadata: &CoreMetadata, options: &SaveOptions) -> CoreMetadata {
+    options
+        .transforms
+        .iter()
+        .fold(metadata.clone(), |metadata, transform| {
+            transform.apply(metadata)
+        })
+}
+
+// --- Writing ---
+
+// Reported after each package entry is copied; sizes are uncompressed bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveProgress {
+    pub entries_done: usize,
+    pub entries_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+impl SaveProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 {
+            0.0
+        } else {
+            self.bytes_done as f32 / self.bytes_total as f32
+        }
+    }
+}
+
+// What a successful save had to do beyond the requested edits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveReport {
+    pub app_rebuilt: bool, // app.xml had an unexpected structure and was regenerated
+    pub future_dates: Vec<MetadataField>, // Saved despite FutureDatePolicy::Warn
+}
+
+// What saving does with dates later than now (plus the allowed tolerance)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FutureDatePolicy {
+    #[default]
+    Ignore,
+    Warn, // Save, listing them in SaveReport::future_dates
+    Block,
+}
+
+// How the entries a save writes itself (core.xml, app.xml and any package
+// file it has to patch) are compressed. Copied entries keep their own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionLevel {
+    #[default]
+    Normal, // Deflate at its default level
+    Store, // No compression
+    Fast,
+    Best,
+}
+
+impl CompressionLevel {
+    pub const ALL: [CompressionLevel; 4] = [
+        CompressionLevel::Normal,
+        CompressionLevel::Store,
+        CompressionLevel::Fast,
+        CompressionLevel::Best,
+    ];
+
+    fn file_options(self) -> FileOptions<'static, ()> {
+        let deflated = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        match self {
+            CompressionLevel::Normal => deflated,
+            CompressionLevel::Store => {
+                FileOptions::default().compression_method(zip::CompressionMethod::Stored)
+            }
+            CompressionLevel::Fast => deflated.compression_level(Some(1)),
+            CompressionLevel::Best => deflated.compression_level(Some(9)),
+        }
+    }
+}
+
+// How save_metadata writes the file; the default is a plain in-place save.
+#[derive(Debug, Clone, Default)]
+pub struct SaveOptions {
+    pub anonymize: bool, // Also blank title, creator and lastModifiedBy in core.xml
+    pub clear_last_modified_by: bool, // Blank only lastModifiedBy
+    // Drop docProps/custom.xml along with its content type and relationship
+    pub remove_custom_properties: bool,
+    // Drop docProps/thumbnail.* along with its relationship
+    pub remove_thumbnail: bool,
+    // Fields whose element is removed, rather than written empty, while
+    // their value is empty
+    pub remove_fields: Vec<MetadataField>,
+    pub verify: bool, // Re-read the written file, restoring the original on failure
+    pub sync_file_times: bool, // Set the file's mtime to dcterms:modified
+    pub sync_created_time: bool, // ...and its creation time, where supported
+    pub block_date_order: bool, // Refuse to save when date_order_issues finds any
+    pub future_dates: FutureDatePolicy,
+    pub future_date_tolerance: Duration, // Clock skew still accepted as "now"
+    // Pretty-print core.xml and app.xml. This reflows the whole part, not
+    // just the edited elements, so every save of such a file changes bytes.
+    pub indent_xml: bool,
+    pub lf_line_endings: bool, // Write core.xml and app.xml with LF only
+    // Date the zip entries of core.xml and app.xml with dcterms:modified
+    // instead of keeping the original entries' timestamps
+    pub stamp_parts_modified: bool,
+    // Make the archive a function of its content alone, so equal input and
+    // metadata always give byte-identical output. Every entry, copied or
+    // written, gets the 1980-01-01 00:00:00 timestamp (overriding
+    // stamp_parts_modified) and 0o644 file permissions as its external
+    // attributes, and no extra fields. Entry order, names, compression and
+    // the compressed data of copied entries are kept.
+    pub reproducible_zip: bool,
+    pub compression: CompressionLevel,
+    pub date_profile: DateProfile,
+    pub transforms: Vec<Arc<dyn MetadataTransform>>, // None by default
+}
+
+// Transforms compare by identity: what one does can't be compared
+impl PartialEq for SaveOptions {
+    fn eq(&self, other: &Self) -> bool {
+        let SaveOptions {
+            anonymize,
+            clear_last_modified_by,
+            remove_custom_properties,
+            remove_thumbnail,
+            remove_fields,
+            verify,
+            sync_file_times,
+            sync_created_time,
+            block_date_order,
+            future_dates,
+            future_date_tolerance,
+            indent_xml,
+            lf_line_endings,
+            stamp_parts_modified,
+            reproducible_zip,
+            compression,
+            date_profile,
+            transforms,
+        } = self;
+        *anonymize == other.anonymize
+            && *clear_last_modified_by == other.clear_last_modified_by
+            && *remove_custom_properties == other.remove_custom_properties
+            && *remove_thumbnail == other.remove_thumbnail
+            && *remove_fields == other.remove_fields
+            && *verify == other.verify
+            && *sync_file_times == other.sync_file_times
+            && *sync_created_time == other.sync_created_time
+            && *block_date_order == other.block_date_order
+            && *future_dates == other.future_dates
+            && *future_date_tolerance == other.future_date_tolerance
+            && *indent_xml == other.indent_xml
+            && *lf_line_endings == other.lf_line_endings
+            && *stamp_parts_modified == other.stamp_parts_modified
+            && *reproducible_zip == other.reproducible_zip
+            && *compression == other.compression
+            && *date_profile == other.date_profile
+            && transforms.len() == other.transforms.len()
+            && transforms
+                .iter()
+                .zip(&other.transforms)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+// Reads the document at `input` and writes the edited copy to `output`;
+// passing the same path for both rewrites the document in place.
+pub fn save_metadata(
+    input: &Path,
+    output: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<SaveReport, DocxMetaError> {
+    save_metadata_with_progress(input, output, metadata, options, &mut |_| true)
+}
+
+// Like save_metadata, calling `progress` as the copy advances. Returning
+// false from it cancels the save: the temp file is removed, the original is
+// left untouched and the error is DocxMetaError::Cancelled.
+pub fn save_metadata_with_progress(
+    input: &Path,
+    output: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+    progress: &mut dyn FnMut(SaveProgress) -> bool,
+) -> Result<SaveReport, DocxMetaError> {
+    let (input, output) = (&long_path(input), &long_path(output));
+    let mut archive = ZipArchive::new(File::open(input)?)?;
+    // Refused before anything is written, so a zip that isn't a document is
+    // never given property parts
+    let kind = detect_document_kind(&mut archive)?;
+    let (metadata, future) = checked(metadata, &stored_metadata(&mut archive), options)?;
+
+    // Next to the output, so the final move normally stays on one volume
+    let (temp_path, temp_file) = create_beside(output, "tmp")?;
+    let result = write_package(&mut archive, temp_file, kind, &metadata, options, progress);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    let report = SaveReport {
+        future_dates: future,
+        ..result?
+    };
+    if options.verify {
+        if let Err(e) = verify_untouched(input, &temp_path, options) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        replace_verified(output, &temp_path)?;
+    } else {
+        move_file(&temp_path, output).map_err(|e| e.context(SaveStep::ReplaceOriginal))?;
+    }
+    if options.sync_file_times {
+        sync_file_times(output, &metadata, options.sync_created_time)
+            .map_err(|e| e.context(SaveStep::SetFileTimes))?;
+    }
+    Ok(report)
+}
+
+// Writes the edited copy of the package read from `reader` to `writer`,
+// without touching the filesystem. Only the options about the package's
+// content apply: verify and the file time options need a path.
+pub fn save_metadata_to_writer<R: Read + Seek, W: Write + Seek>(
+    reader: R,
+    writer: W,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<SaveReport, DocxMetaError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let kind = detect_document_kind(&mut archive)?;
+    let (metadata, future) = checked(metadata, &stored_metadata(&mut archive), options)?;
+    let report = write_package(&mut archive, writer, kind, &metadata, options, &mut |_| {
+        true
+    })?;
+    Ok(SaveReport {
+        future_dates: future,
+        ..report
+    })
+}
+
+// The metadata as a save of the package at `path` would write it: transformed
+// and normalized, with the dates the package already held in reduced form
+// kept. The date checks are left out, as they refuse a save rather than
+// change what it writes.
+pub fn saved_metadata(
+    path: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<CoreMetadata, DocxMetaError> {
+    PropertyParts::read(path)?.saved_metadata(metadata, options)
+}
+
+// A package's property parts, read once so a preview can regenerate them for
+// every edit without reopening the archive. For an OpenDocument text the core
+// part is meta.xml.
+pub struct PropertyParts {
+    kind: DocumentKind,
+    core: Option<XmlPart>,
+    app: Option<XmlPart>,
+    stored: CoreMetadata,
+}
+
+impl PropertyParts {
+    pub fn read(path: &Path) -> Result<Self, DocxMetaError> {
+        let mut archive = open_document(path)?;
+        let kind = detect_document_kind(&mut archive)?;
+        let core = if kind.is_open_document() {
+            read_xml_part(&mut archive, ODT_META_PART)?
+        } else {
+            read_core_part(&mut archive)?
+        };
+        Ok(PropertyParts {
+            kind,
+            core,
+            app: read_xml_part(&mut archive, APP_PART.name)?,
+            stored: stored_metadata(&mut archive),
+        })
+    }
+
+    // The parts as they are in the package; None when missing
+    pub fn core(&self) -> Option<&str> {
+        self.core.as_ref().map(|part| part.text.as_str())
+    }
+
+    pub fn app(&self) -> Option<&str> {
+        self.app.as_ref().map(|part| part.text.as_str())
+    }
+
+    // See saved_metadata
+    pub fn saved_metadata(
+        &self,
+        metadata: &CoreMetadata,
+        options: &SaveOptions,
+    ) -> Result<CoreMetadata, DocxMetaError> {
+        normalized(
+            &transformed(metadata, options),
+            &self.stored,
+            options.date_profile,
+        )
+    }
+
+    // The edited parts, for metadata already in its saved form
+    pub fn core_xml(
+        &self,
+        metadata: &CoreMetadata,
+        options: &SaveOptions,
+    ) -> Result<String, DocxMetaError> {
+        if self.kind.is_open_document() {
+            odt_meta_xml(self.core.as_ref(), metadata, options)
+        } else {
+            core_xml(self.core.as_ref(), metadata, options)
+        }
+    }
+
+    pub fn app_xml(
+        &self,
+        metadata: &CoreMetadata,
+        options: &SaveOptions,
+    ) -> Result<String, DocxMetaError> {
+        rewrite_app_xml(self.app.as_ref(), metadata, self.kind, options).map(|(xml, _)| xml)
+    }
+}
+
+// Runs the transforms, normalizes the result and applies the date checks the
+// options ask for, returning the future dates FutureDatePolicy::Warn lets through
+fn checked(
+    metadata: &CoreMetadata,
+    stored: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<(CoreMetadata, Vec<MetadataField>), DocxMetaError> {
+    let metadata = normalized(
+        &transformed(metadata, options),
+        stored,
+        options.date_profile,
+    )?;
+    if options.block_date_order {
+        if let Some(issue) = date_order_issues(&metadata).first() {
+            return Err(DocxMetaError::DateOrder(*issue));
+        }
+    }
+    let future = match options.future_dates {
+        FutureDatePolicy::Ignore => Vec::new(),
+        policy => {
+            let future = future_dates(&metadata, options.future_date_tolerance);
+            if policy == FutureDatePolicy::Block && !future.is_empty() {
+                return Err(DocxMetaError::FutureDates(future));
+            }
+            future
+        }
+    };
+    Ok((metadata, future))
+}
+
+// The date fields later than now plus `tolerance`; unparsable dates are left
+// to the format validation
+pub fn future_dates(metadata: &CoreMetadata, tolerance: Duration) -> Vec<MetadataField> {
+    let limit = Utc::now() + tolerance;
+    [
+        MetadataField::Created,
+        MetadataField::Modified,
+        MetadataField::LastPrinted,
+    ]
+    .into_iter()
+    .filter(|field| parse_date(metadata.get(*field)).is_some_and(|date| date > limit))
+    .collect()
+}
+
+// The package's values as they stand, for telling which fields were edited.
+// Best-effort: whatever fails to read counts as empty, and the save itself
+// reports the problem.
+fn stored_metadata<R: Read + Seek>(archive: &mut ZipArchive<R>) -> CoreMetadata {
+    read_metadata(archive).unwrap_or_default()
+}
+
+// Validates the dates and counts, returning them in the form they are saved in
+fn normalized(
+    metadata: &CoreMetadata,
+    stored: &CoreMetadata,
+    profile: DateProfile,
+) -> Result<CoreMetadata, DocxMetaError> {
+    let date =
+        |field| normalize_edited_date(metadata.get(field), stored.get(field), field, profile);
+    Ok(CoreMetadata {
+        created: date(MetadataField::Created)?,
+        modified: date(MetadataField::Modified)?,
+        last_printed: if metadata.last_printed.trim().is_empty() {
+            String::new()
+        } else {
+            date(MetadataField::LastPrinted)?
+        },
+        revision: normalize_count_field(&metadata.revision, MetadataField::Revision)?,
+        total_time: normalize_count_field(&metadata.total_time, MetadataField::TotalTime)?,
+        pages: normalize_count_field(&metadata.pages, MetadataField::Pages)?,
+        words: normalize_count_field(&metadata.words, MetadataField::Words)?,
+        characters: normalize_count_field(&metadata.characters, MetadataField::Characters)?,
+        doc_security: normalize_doc_security(&metadata.doc_security)?,
+        app_version: normalize_app_version(&metadata.app_version)?,
+        scale_crop: normalize_flag_field(&metadata.scale_crop, MetadataField::ScaleCrop)?,
+        links_up_to_date: normalize_flag_field(
+            &metadata.links_up_to_date,
+            MetadataField::LinksUpToDate,
+        )?,
+        shared_doc: normalize_flag_field(&metadata.shared_doc, MetadataField::SharedDoc)?,
+        ..metadata.clone()
+    })
+}
+
+// A reduced-precision date the package already held, such as a date-only
+// dcterms:created, is written back verbatim; only a date that was edited has
+// to satisfy `profile`.
+fn normalize_edited_date(
+    value: &str,
+    stored: &str,
+    field: MetadataField,
+    profile: DateProfile,
+) -> Result<String, DocxMetaError> {
+    if value == stored && is_reduced_date(value) {
+        Ok(value.to_string())
+    } else {
+        normalize_date_field(value, field, profile)
+    }
+}
+
+// Office rejects an app.xml whose AppVersion is not of the form "XX.YYYY"
+fn normalize_app_version(value: &str) -> Result<String, DocxMetaError> {
+    let value = value.trim();
+    let valid = value.is_empty()
+        || value.split_once('.').is_some_and(|(major, minor)| {
+            major.len() == 2
+                && minor.len() == 4
+                && (major.bytes().chain(minor.bytes())).all(|b| b.is_ascii_digit())
+        });
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(DocxMetaError::InvalidAppVersion(value.to_string()))
+    }
+}
+
+// --- Dry Run ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,   // The field was empty or its element absent
+    Changed, // Both values non-empty
+    Removed, // The new value is empty
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: MetadataField,
+    pub old: String,
+    pub new: String,
+}
+
+impl FieldChange {
+    pub fn kind(&self) -> ChangeKind {
+        if self.old.is_empty() {
+            ChangeKind::Added
+        } else if self.new.is_empty() {
+            ChangeKind::Removed
+        } else {
+            ChangeKind::Changed
+        }
+    }
+}
+
+// Runs the same validation and XML generation as a save, then reports which
+// fields the written parts would change. Nothing is written to disk.
+pub fn dry_run(
+    path: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<Vec<FieldChange>, DocxMetaError> {
+    let mut archive = open_document(path)?;
+    let mut before = CoreMetadata::default();
+    let mut after = CoreMetadata::default();
+    if is_open_document_text(&mut archive) {
+        let meta = read_xml_part(&mut archive, ODT_META_PART)?;
+        if let Some(meta) = &meta {
+            parse_odt_meta(&meta.text, &mut before)?;
+        }
+        let metadata = normalized(
+            &transformed(metadata, options),
+            &before,
+            options.date_profile,
+        )?;
+        parse_odt_meta(
+            &odt_meta_xml(meta.as_ref(), &metadata, options)?,
+            &mut after,
+        )?;
+    } else {
+        let (core_props, app_props) = read_property_parts(&mut archive)?;
+        if let Some(core_props) = &core_props {
+            parse_core_properties(&core_props.text, &mut before)?;
+        }
+        if let Some(app_props) = &app_props {
+            parse_app_properties(&app_props.text, &mut before)?;
+        }
+        let metadata = normalized(
+            &transformed(metadata, options),
+            &before,
+            options.date_profile,
+        )?;
+        parse_core_properties(
+            &core_xml(core_props.as_ref(), &metadata, options)?,
+            &mut after,
+        )?;
+        let kind = detect_document_kind(&mut archive)?;
+        let (app_xml, _) = rewrite_app_xml(app_props.as_ref(), &metadata, kind, options)?;
+        parse_app_properties(&app_xml, &mut after)?;
+    }
+    Ok(MetadataField::ALL
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| FieldChange {
+            field,
+            old: before.get(field).to_string(),
+            new: after.get(field).to_string(),
+        })
+        .collect())
+}
+
+// What a save would reject, found without writing anything: whether the file
+// is a package whose property parts parse, whether each date parses, whether
+// the dates are in order, and whether the remaining values are valid. Empty
+// when nothing is wrong. The other fields are only checked once the dates
+// pass, as normalization stops at the first error.
+pub fn validate(path: &Path, metadata: &CoreMetadata, options: &SaveOptions) -> Vec<DocxMetaError> {
+    let mut problems = Vec::new();
+    let stored = match open_document(path).and_then(|mut archive| read_metadata(&mut archive)) {
+        Ok(stored) => stored,
+        Err(e) => {
+            problems.push(e);
+            CoreMetadata::default()
+        }
+    };
+    let metadata = transformed(metadata, options);
+    let mut dates = metadata.clone();
+    let package_problems = problems.len();
+    for field in [
+        MetadataField::Created,
+        MetadataField::Modified,
+        MetadataField::LastPrinted,
+    ] {
+        let value = metadata.get(field);
+        if field == MetadataField::LastPrinted && value.trim().is_empty() {
+            continue;
+        }
+        match normalize_edited_date(value, stored.get(field), field, options.date_profile) {
+            Ok(date) => *dates.get_mut(field) = date,
+            Err(e) => problems.push(e),
+        }
+    }
+    let dates_valid = problems.len() == package_problems;
+    problems.extend(
+        date_order_issues(&dates)
+            .into_iter()
+            .map(DocxMetaError::DateOrder),
+    );
+    if dates_valid {
+        if let Err(e) = normalized(&metadata, &stored, options.date_profile) {
+            problems.push(e);
+        }
+    }
+    problems
+}
+
+// A new file next to `path`, named "<name>.<extension>" or, when that is
+// taken, "<name>.1.<extension>" and so on. Created with create_new, so a file
+// already there is never truncated or replaced.
+fn create_beside(path: &Path, extension: &str) -> io::Result<(PathBuf, File)> {
+    for n in 0.. {
+        let mut name = path.as_os_str().to_owned();
+        match n {
+            0 => name.push(format!(".{}", extension)),
+            n => name.push(format!(".{}.{}", n, extension)),
+        }
+        match OpenOptions::new().write(true).create_new(true).open(&name) {
+            Ok(file) => return Ok((PathBuf::from(name), file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
+
+// fs::rename, falling back to copy-then-delete when the two paths are on
+// different volumes (as happens with some network shares)
+fn move_file(from: &Path, to: &Path) -> Result<(), DocxMetaError> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(from, to).map_err(|copy| {
+                DocxMetaError::from(copy).context(SaveStep::CopyAcrossVolumes(e))
+            })?;
+            fs::remove_file(from)
+                .map_err(|e| DocxMetaError::from(e).context(SaveStep::RemoveTempFile))
+        }
+        result => Ok(result?),
+    }
+}
+
+// Windows refuses paths over MAX_PATH (260 characters) unless they carry the
+// extended-length prefix, which in turn requires an absolute, normalized path
+#[cfg(windows)]
+fn long_path(path: &Path) -> PathBuf {
+    const MAX_PATH: usize = 260;
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let text = absolute.to_string_lossy();
+    if text.len() < MAX_PATH || text.starts_with(r"\\?\") {
+        return absolute;
+    }
+    match text.strip_prefix(r"\\") {
+        // \\server\share\... becomes \\?\UNC\server\share\...
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", text)),
+    }
+}
+
+#[cfg(not(windows))]
+fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+// Every entry a save doesn't mean to change must come out byte for byte
+// identical. Raw copies keep the compressed data, so comparing the sizes and
+// CRCs recorded in the two central directories is enough to tell.
+fn verify_untouched(
+    original: &Path,
+    written: &Path,
+    options: &SaveOptions,
+) -> Result<(), DocxMetaError> {
+    let mut before = ZipArchive::new(File::open(original)?)?;
+    let mut after = ZipArchive::new(File::open(written)?)?;
+    let core_name = core_part_name(&mut before);
+    // meta.xml is rewritten, and listed in the manifest when it is created
+    let open_document = is_open_document_text(&mut before);
+    let creates_meta = open_document && before.index_for_name(ODT_META_PART).is_none();
+    // Creating or removing a property part also updates these two
+    let removed_thumbnail = thumbnail_part(&before).filter(|_| options.remove_thumbnail);
+    let registers_parts = core_name.is_none()
+        || options.remove_custom_properties
+        || removed_thumbnail.is_some()
+        || [APP_PART.name, PACKAGE_RELS]
+            .iter()
+            .any(|name| before.index_for_name(name).is_none());
+    for i in 0..before.len() {
+        let entry = before.by_index_raw(i)?;
+        let name = entry.name();
+        let rewritten = Some(name) == core_name.as_deref()
+            || name == APP_PART.name
+            || (options.remove_custom_properties && name == CUSTOM_PART.name)
+            || removed_thumbnail.as_deref() == Some(name)
+            || (registers_parts && matches!(name, CONTENT_TYPES | PACKAGE_RELS))
+            || (open_document && name == ODT_META_PART)
+            || (creates_meta && name == ODT_MANIFEST);
+        if rewritten {
+            continue;
+        }
+        let unchanged = match after.index_for_name(name) {
+            Some(index) => {
+                let copy = after.by_index_raw(index)?;
+                copy.crc32() == entry.crc32()
+                    && copy.size() == entry.size()
+                    && copy.compressed_size() == entry.compressed_size()
+                    && copy.compression() == entry.compression()
+            }
+            None => false,
+        };
+        if !unchanged {
+            return Err(DocxMetaError::PartModified(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
+// Swaps the new file in while keeping the original as a backup (named as
+// create_beside picks, so a "<name>.bak" of the user's own is left alone), and
+// only drops that backup once the new file reads back cleanly. When saving
+// to a new path there is nothing to back up, and a bad copy is just removed.
+fn replace_verified(path: &Path, temp_path: &Path) -> Result<(), DocxMetaError> {
+    if !path.exists() {
+        move_file(temp_path, path).map_err(|e| e.context(SaveStep::WriteNewFile))?;
+        return verify_document(path).map_err(|e| {
+            let _ = fs::remove_file(path);
+            e.context(SaveStep::VerifyRemovedNew)
+        });
+    }
+    // The name is reserved with an empty file, which the rename then replaces
+    let (backup_path, _) = create_beside(path, "bak")
+        .map_err(|e| DocxMetaError::from(e).context(SaveStep::CreateBackup))?;
+    let backup_path = backup_path.as_path();
+    if let Err(e) = fs::rename(path, backup_path) {
+        let _ = fs::remove_file(backup_path);
+        return Err(DocxMetaError::from(e).context(SaveStep::CreateBackup));
+    }
+    if let Err(e) = move_file(temp_path, path) {
+        let _ = fs::rename(backup_path, path);
+        let _ = fs::remove_file(temp_path);
+        return Err(e.context(SaveStep::ReplaceOriginal));
+    }
+    match verify_document(path) {
+        Ok(()) => {
+            let _ = fs::remove_file(backup_path);
+            Ok(())
+        }
+        Err(e) => {
+            if let Err(restore) = fs::rename(backup_path, path) {
+                return Err(DocxMetaError::from(restore)
+                    .context(SaveStep::VerifyRestoreFailed(Box::new(e))));
+            }
+            Err(e.context(SaveStep::VerifyRestored))
+        }
+    }
+}
+
+fn write_package<R: Read + Seek, W: Write + Seek>(
+    archive: &mut ZipArchive<R>,
+    output: W,
+    kind: DocumentKind, // Picks the app.xml elements worth inserting
+    metadata: &CoreMetadata,
+    save_options: &SaveOptions,
+    progress: &mut dyn FnMut(SaveProgress) -> bool,
+) -> Result<SaveReport, DocxMetaError> {
+    // An OpenDocument text has meta.xml in place of core.xml and app.xml,
+    // and a manifest in place of the content types and relationships
+    let open_document = kind.is_open_document();
+    let creates_meta = open_document && archive.index_for_name(ODT_META_PART).is_none();
+    // core.xml is rewritten wherever the package keeps it
+    let core_name = core_part_name(archive);
+    let creates_core = !open_document && core_name.is_none();
+    let creates_app = !open_document && archive.index_for_name(APP_PART.name).is_none();
+    // Parts we are about to create must also be registered in the package
+    let mut new_parts: Vec<&PackagePart> = Vec::new();
+    if creates_core {
+        new_parts.push(&CORE_PART);
+    }
+    if creates_app {
+        new_parts.push(&APP_PART);
+    }
+    // Stripped-down packages may lack _rels/.rels, leaving even the main
+    // part unlinked; one is created so Office can open the result
+    let creates_package_rels = !open_document && archive.index_for_name(PACKAGE_RELS).is_none();
+    let removes_custom =
+        save_options.remove_custom_properties && archive.index_for_name(CUSTOM_PART.name).is_some();
+    let removed_thumbnail =
+        thumbnail_part(archive).filter(|_| save_options.remove_thumbnail && !open_document);
+    let mut zip_writer = ZipWriter::new(output);
+    zip_writer.set_raw_comment(archive.comment().into());
+    let options = save_options.compression.file_options();
+    let mut status = SaveProgress {
+        entries_done: 0,
+        entries_total: archive.len(),
+        bytes_done: 0,
+        bytes_total: (0..archive.len())
+            .filter_map(|i| archive.by_index_raw(i).ok().map(|file| file.size()))
+            .sum(),
+    };
+    // Dates the property parts' entries with dcterms:modified instead of
+    // keeping the original timestamps
+    let stamped_options = zip_time(&metadata.modified)
+        .filter(|_| save_options.stamp_parts_modified && !save_options.reproducible_zip)
+        .map(|time| options.last_modified_time(time));
+    let mut app_rebuilt = false;
+    // Entries of a reproducible archive are dated zip::DateTime::default(),
+    // the earliest DOS timestamp: 1980-01-01 00:00:00
+    let reproducible_options = options
+        .last_modified_time(zip::DateTime::default())
+        .unix_permissions(REPRODUCIBLE_PERMISSIONS);
+
+    for i in 0..archive.len() {
+        if !progress(status) {
+            return Err(DocxMetaError::Cancelled);
+        }
+        let file = archive.by_index_raw(i)?;
+        let file_name = file.name().to_string();
+        status.entries_done += 1;
+        status.bytes_done += file.size();
+        // Keep each entry's timestamp; the writer would otherwise use "now"
+        let entry_options = match file.last_modified() {
+            _ if save_options.reproducible_zip => reproducible_options,
+            Some(time) => options.last_modified_time(time),
+            None => options,
+        };
+        // The property parts are written where they were, so the entry order
+        // stays the original's. One whose values don't change is copied.
+        if open_document && file_name == ODT_META_PART {
+            drop(file);
+            let meta = read_xml_entry(archive, i, &file_name)?;
+            let changed = save_options.anonymize
+                || save_options.clear_last_modified_by
+                || odt_meta_changed(&meta, metadata)?;
+            if changed {
+                let xml = format_xml(
+                    ODT_META_PART,
+                    odt_meta_xml(Some(&meta), metadata, save_options)?,
+                    save_options,
+                )?;
+                let part_options = stamped_options.unwrap_or(entry_options);
+                write_part(&mut zip_writer, &file_name, &xml, part_options)?;
+            } else {
+                copy_entry(&mut zip_writer, archive.by_index_raw(i)?, save_options)?;
+            }
+            continue;
+        }
+        if !open_document && Some(file_name.as_str()) == core_name.as_deref() {
+            drop(file);
+            let core_props = read_xml_entry(archive, i, &file_name)?;
+            let changed = save_options.anonymize
+                || save_options.clear_last_modified_by
+                || part_changed(
+                    &core_props,
+                    metadata,
+                    save_options,
+                    core_element,
+                    parse_core_properties,
+                )?;
+            if changed {
+                let xml = format_xml(
+                    CORE_PART.name,
+                    core_xml(Some(&core_props), metadata, save_options)?,
+                    save_options,
+                )?;
+                let part_options = stamped_options.unwrap_or(entry_options);
+                write_part(&mut zip_writer, &file_name, &xml, part_options)?;
+            } else {
+                copy_entry(&mut zip_writer, archive.by_index_raw(i)?, save_options)?;
+            }
+            continue;
+        }
+        if !open_document && file_name == APP_PART.name {
+            drop(file);
+            let app_props = read_xml_entry(archive, i, &file_name)?;
+            let changed = part_changed(
+                &app_props,
+                metadata,
+                save_options,
+                app_element,
+                parse_app_properties,
+            )?;
+            if changed {
+                let (xml, rebuilt) =
+                    rewrite_app_xml(Some(&app_props), metadata, kind, save_options)?;
+                app_rebuilt = rebuilt;
+                let xml = format_xml(APP_PART.name, xml, save_options)?;
+                let part_options = stamped_options.unwrap_or(entry_options);
+                write_part(&mut zip_writer, &file_name, &xml, part_options)?;
+            } else {
+                copy_entry(&mut zip_writer, archive.by_index_raw(i)?, save_options)?;
+            }
+            continue;
+        }
+        if removes_custom && file_name == CUSTOM_PART.name {
+            continue;
+        }
+        if removed_thumbnail.as_ref() == Some(&file_name) {
+            continue;
+        }
+        let removes_parts = removes_custom || removed_thumbnail.is_some();
+        let registers_parts = match file_name.as_str() {
+            CONTENT_TYPES => !new_parts.is_empty() || creates_package_rels || removes_parts,
+            PACKAGE_RELS => !new_parts.is_empty() || removes_parts,
+            ODT_MANIFEST => creates_meta,
+            _ => false,
+        };
+        if !registers_parts {
+            // Copy everything else still compressed: the entry stays byte for
+            // byte identical, and large embedded media is never inflated
+            copy_entry(&mut zip_writer, file, save_options)?;
+            continue;
+        }
+        drop(file);
+        zip_writer.start_file(file_name.as_str(), entry_options)?;
+        let mut buffer = Vec::new();
+        archive.by_index(i)?.read_to_end(&mut buffer)?;
+        if creates_meta && file_name == ODT_MANIFEST {
+            buffer = add_manifest_entry(&buffer).map_err(|e| DocxMetaError::xml(&file_name, e))?;
+        }
+        if creates_package_rels && file_name == CONTENT_TYPES {
+            buffer = add_content_type_default(&buffer, "rels", RELATIONSHIPS_CONTENT_TYPE)
+                .map_err(|e| DocxMetaError::xml(&file_name, e))?;
+        }
+        for part in &new_parts {
+            buffer = match file_name.as_str() {
+                CONTENT_TYPES => add_content_type_override(&buffer, part),
+                _ => add_package_relationship(&buffer, part),
+            }
+            .map_err(|e| DocxMetaError::xml(&file_name, e))?;
+        }
+        if removes_custom {
+            buffer = match file_name.as_str() {
+                CONTENT_TYPES => remove_content_type_override(&buffer, CUSTOM_PART.name),
+                _ => remove_package_relationship(&buffer, CUSTOM_PART.relationship_type),
+            }
+            .map_err(|e| DocxMetaError::xml(&file_name, e))?;
+        }
+        if let Some(thumbnail) = &removed_thumbnail {
+            buffer = match file_name.as_str() {
+                CONTENT_TYPES => remove_content_type_override(&buffer, thumbnail),
+                _ => remove_package_relationship(&buffer, THUMBNAIL_RELATIONSHIP),
+            }
+            .map_err(|e| DocxMetaError::xml(&file_name, e))?;
+        }
+        zip_writer.write_all(&buffer)?;
+    }
+
+    if creates_package_rels {
+        let mut rels = default_package_rels(kind.main_part())
+            .map_err(|e| DocxMetaError::xml(PACKAGE_RELS, e))?;
+        for part in [&CORE_PART, &APP_PART] {
+            rels = add_package_relationship(&rels, part)
+                .map_err(|e| DocxMetaError::xml(PACKAGE_RELS, e))?;
+        }
+        zip_writer.start_file(
+            PACKAGE_RELS,
+            if save_options.reproducible_zip {
+                reproducible_options
+            } else {
+                options
+            },
+        )?;
+        zip_writer.write_all(&rels)?;
+    }
+
+    // Parts the package lacked go last, after everything it had
+    let new_part_options = stamped_options.unwrap_or(if save_options.reproducible_zip {
+        reproducible_options
+    } else {
+        options
+    });
+    if creates_meta {
+        let xml = format_xml(
+            ODT_META_PART,
+            odt_meta_xml(None, metadata, save_options)?,
+            save_options,
+        )?;
+        write_part(&mut zip_writer, ODT_META_PART, &xml, new_part_options)?;
+    }
+    if creates_core {
+        let xml = format_xml(
+            CORE_PART.name,
+            core_xml(None, metadata, save_options)?,
+            save_options,
+        )?;
+        write_part(&mut zip_writer, CORE_PART.name, &xml, new_part_options)?;
+    }
+    if creates_app {
+        let (xml, _) = rewrite_app_xml(None, metadata, kind, save_options)?;
+        let xml = format_xml(APP_PART.name, xml, save_options)?;
+        write_part(&mut zip_writer, APP_PART.name, &xml, new_part_options)?;
+    }
+
+    zip_writer.finish()?;
+    Ok(SaveReport {
+        app_rebuilt,
+        ..SaveReport::default()
+    })
+}
+
+fn write_part<W: Write + Seek>(
+    zip_writer: &mut ZipWriter<W>,
+    name: &str,
+    xml: &str,
+    options: FileOptions<()>,
+) -> Result<(), DocxMetaError> {
+    zip_writer.start_file(name, options)?;
+    zip_writer.write_all(xml.as_bytes())?;
+    Ok(())
+}
+
+fn copy_entry<R: Read, W: Write + Seek>(
+    zip_writer: &mut ZipWriter<W>,
+    file: ZipFile<'_, R>,
+    save_options: &SaveOptions,
+) -> Result<(), DocxMetaError> {
+    if save_options.reproducible_zip {
+        zip_writer.raw_copy_file_touch(
+            file,
+            zip::DateTime::default(),
+            Some(REPRODUCIBLE_PERMISSIONS),
+        )?;
+    } else {
+        zip_writer.raw_copy_file(file)?;
+    }
+    Ok(())
+}
+
+// Whether saving changes any value an existing property part holds, or
+// drops one of its elements. A part that would keep everything is copied as
+// it is, so an edit to core.xml alone never reformats app.xml.
+fn part_changed(
+    original: &XmlPart,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+    element: fn(MetadataField) -> Option<&'static str>,
+    parse: fn(&str, &mut CoreMetadata) -> Result<(), DocxMetaError>,
+) -> Result<bool, DocxMetaError> {
+    let mut before = CoreMetadata::default();
+    parse(&original.text, &mut before)?;
+    let edited = MetadataField::ALL
+        .into_iter()
+        .filter(|field| element(*field).is_some())
+        .any(|field| before.get(field) != metadata.get(field));
+    let present = child_names(&original.text);
+    let drops = dropped_elements(metadata, options, element)
+        .iter()
+        .filter_map(|name| name.rsplit(':').next())
+        .any(|local| present.iter().any(|name| name == local.as_bytes()));
+    Ok(edited || drops)
+}
+
+// The external attributes of every entry of a reproducible archive: rw-r--r--
+const REPRODUCIBLE_PERMISSIONS: u32 = 0o644;
+
+// Zip entries carry a DOS timestamp without a timezone, here the UTC wall
+// clock. Dates before 1980 can't be represented and give None.
+fn zip_time(value: &str) -> Option<zip::DateTime> {
+    let date = parse_date(value)?;
+    zip::DateTime::from_date_and_time(
+        u16::try_from(date.year()).ok()?,
+        date.month() as u8,
+        date.day() as u8,
+        date.hour() as u8,
+        date.minute() as u8,
+        date.second() as u8,
+    )
+    .ok()
+}
+
+// For an OpenDocument text, the edited meta.xml
+pub fn generate_core_xml(
+    original_path: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<String, DocxMetaError> {
+    PropertyParts::read(original_path)?.core_xml(metadata, options)
+}
+
+// The edited core.xml, from the original part or, without one, from scratch
+fn core_xml(
+    core_props: Option<&XmlPart>,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<String, DocxMetaError> {
+    let Some(core_props) = core_props else {
+        return default_core_xml(metadata);
+    };
+    let mut reader = Reader::from_str(&core_props.text);
+    let mut writer = Writer::new(core_props.output_prefix());
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut in_target_elem = false;
+    let dropped = dropped_elements(metadata, options, core_element);
+    let mut dropping = false;
+    // Elements already given their new value. Some generators repeat e.g.
+    // dcterms:modified; only the first copy is kept, so the new value isn't
+    // written twice or contradicted by a stale duplicate.
+    let mut replaced: Vec<Vec<u8>> = Vec::new();
+    // Optional elements that are inserted when the part lacks them:
+    // (qualified name, new value, seen in the original)
+    let mut optional = [
+        ("cp:revision", metadata.revision.as_str(), false),
+        ("cp:contentStatus", metadata.content_status.as_str(), false),
+        ("dc:description", metadata.description.as_str(), false),
+        ("cp:keywords", metadata.keywords.as_str(), false),
+        ("dc:language", metadata.language.as_str(), false),
+        ("cp:category", metadata.category.as_str(), false),
+        ("cp:version", metadata.version.as_str(), false),
+        ("dc:identifier", metadata.identifier.as_str(), false),
+    ];
+    // The dates are inserted too, typed or not to match the part's own
+    let mut dates = [
+        ("dcterms:created", metadata.created.as_str(), false),
+        ("dcterms:modified", metadata.modified.as_str(), false),
+    ];
+    let typed_dates = uses_w3cdtf_type(&core_props.text);
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e))
+                if depth == 1
+                    && (is_dropped(&dropped, e.name().as_ref())
+                        || replaced.iter().any(|name| name == e.name().as_ref())) =>
+            {
+                // A cleared field or a duplicate: leave out the element and its text
+                depth += 1;
+                dropping = true;
+            }
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                let text_to_write = match e.name().as_ref() {
+                    b"cp:lastPrinted" => Some(metadata.last_printed.as_str()),
+                    b"dc:title" | b"dc:creator" | b"cp:lastModifiedBy" if options.anonymize => {
+                        Some("")
+                    }
+                    b"cp:lastModifiedBy" if options.clear_last_modified_by => Some(""),
+                    name => mark_seen(&mut dates, name).or_else(|| mark_seen(&mut optional, name)),
+                };
+                // Start tags are re-emitted from their raw bytes, so attributes,
+                // namespace declarations and quoting survive exactly; only the
+                // root may gain a declaration below.
+                let mut start = e.to_owned();
+                if depth == 1 {
+                    // The root must bind the prefix of every element we may insert
+                    for (name, value, _) in optional.iter().chain(&dates) {
+                        if !value.is_empty() {
+                            declare_namespace(&mut start, name, &CORE_NAMESPACES);
+                        }
+                    }
+                    if typed_dates && dates.iter().any(|(_, value, _)| !value.is_empty()) {
+                        declare_namespace(&mut start, "xsi:type", &CORE_NAMESPACES);
+                    }
+                }
+                writer.write_event(Event::Start(start))?;
+                if let Some(text_to_write) = text_to_write {
+                    if depth == 2 {
+                        replaced.push(e.name().as_ref().to_vec());
+                    }
+                    in_target_elem = true;
+                    writer.write_event(Event::Text(BytesText::new(text_to_write)))?;
+                }
+            }
+            Ok(Event::Text(_)) | Ok(Event::CData(_)) | Ok(Event::GeneralRef(_))
+                if in_target_elem || dropping =>
+            {
+                // Skip the original text content of the target element
+            }
+            Ok(Event::Empty(e))
+                if depth == 1
+                    && (is_dropped(&dropped, e.name().as_ref())
+                        || replaced.iter().any(|name| name == e.name().as_ref())) => {}
+            Ok(Event::Empty(e)) if depth == 1 => {
+                // Expand e.g. <cp:keywords/> so it can carry the new value
+                let value = mark_seen(&mut dates, e.name().as_ref())
+                    .or_else(|| mark_seen(&mut optional, e.name().as_ref()));
+                if value.is_some() {
+                    replaced.push(e.name().as_ref().to_vec());
+                }
+                match value {
+                    Some(value) if !value.is_empty() => {
+                        writer.write_event(Event::Start(e.to_owned()))?;
+                        writer.write_event(Event::Text(BytesText::new(value)))?;
+                        writer.write_event(Event::End(e.to_end()))?;
+                    }
+                    _ => writer.write_event(Event::Empty(e))?,
+                }
+            }
+            Ok(Event::End(_)) if dropping => {
+                dropping = false;
+                depth = depth.saturating_sub(1);
+            }
+            Ok(Event::End(e)) => {
+                if depth == 1 {
+                    // Insert missing elements just before </cp:coreProperties>
+                    for (name, value, seen) in optional {
+                        if !seen && !value.is_empty() {
+                            write_text_element(&mut writer, name, value)?;
+                        }
+                    }
+                    for (name, value, seen) in dates {
+                        if !seen && !value.is_empty() {
+                            write_date_element(&mut writer, name, value, typed_dates)?;
+                        }
+                    }
+                }
+                // Target elements hold only text, so any end tag closes them
+                in_target_elem = false;
+                depth = depth.saturating_sub(1);
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Ok(Event::Decl(e)) => {
+                // Not left to the catch-all arm below: the text was decoded
+                // to UTF-8 up front, so a transcoded part's declaration must
+                // stop naming its old encoding. Otherwise it is re-emitted
+                // as is (standalone, quoting).
+                writer.write_event(Event::Decl(core_props.declaration(e)))?;
+            }
+            Ok(Event::Eof) => break,
+            Ok(e) => {
+                writer.write_event(e)?;
+            }
+            Err(e) => {
+                return Err(DocxMetaError::xml_at(
+                    CORE_PART.name,
+                    e,
+                    &core_props.text,
+                    &reader,
+                ))
+            }
+        }
+        buf.clear();
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|e| DocxMetaError::xml(CORE_PART.name, e))
+}
+
+pub fn generate_app_xml(
+    original_path: &Path,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<String, DocxMetaError> {
+    PropertyParts::read(original_path)?.app_xml(metadata, options)
+}
+
+// Also reports whether the part had to be rebuilt: edits are only inserted
+// at the end of a <Properties> root, so an app.xml with any other root (or
+// an empty <Properties/>) is replaced by a default one rather than silently
+// dropping them. Elements `kind` doesn't use are edited where the part has
+// them but never inserted.
+fn rewrite_app_xml(
+    app_props: Option<&XmlPart>,
+    metadata: &CoreMetadata,
+    kind: DocumentKind,
+    options: &SaveOptions,
+) -> Result<(String, bool), DocxMetaError> {
+    let Some(app_props) = app_props else {
+        return Ok((default_app_xml(metadata, kind)?, false));
+    };
+    let mut reader = Reader::from_str(&app_props.text);
+    let mut writer = Writer::new(app_props.output_prefix());
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut in_target_elem = false;
+    let dropped = dropped_elements(metadata, options, app_element);
+    let mut dropping = false;
+    // Elements we edit, by local name: (name, new value, seen in the
+    // original). Anything the part lacks is inserted where Word would put it,
+    // before the first later element the part does have.
+    let present = child_names(&app_props.text);
+    let mut optional = app_fields(metadata).map(|(name, value)| {
+        let seen = present.iter().any(|present| present == name.as_bytes());
+        let value = if seen || kind.uses_app_element(name) {
+            value
+        } else {
+            ""
+        };
+        (name, value, seen)
+    });
+    // Inserted elements must reuse whatever prefix the root binds to the
+    // extended-properties namespace (usually none, i.e. the default namespace).
+    let mut root_prefix: Option<String> = None;
+    let mut root_closed = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if depth == 1 && is_dropped(&dropped, e.local_name().as_ref()) => {
+                depth += 1;
+                dropping = true;
+            }
+            Ok(Event::Start(e)) => {
+                if depth == 1 {
+                    let prefix = root_prefix.as_deref();
+                    insert_app_elements_before(
+                        &mut writer,
+                        &mut optional,
+                        prefix,
+                        e.local_name().as_ref(),
+                    )?;
+                }
+                depth += 1;
+                if depth == 1 {
+                    if e.local_name().as_ref() != b"Properties" {
+                        return Ok((default_app_xml(metadata, kind)?, true));
+                    }
+                    root_prefix = e
+                        .name()
+                        .prefix()
+                        .map(|p| String::from_utf8_lossy(p.as_ref()).into_owned());
+                }
+                writer.write_event(Event::Start(e.to_owned()))?;
+                if depth == 2 {
+                    if let Some(value) = mark_seen(&mut optional, e.local_name().as_ref()) {
+                        in_target_elem = true;
+                        writer.write_event(Event::Text(BytesText::new(value)))?;
+                    }
+                }
+            }
+            Ok(Event::Empty(e)) if depth == 1 && is_dropped(&dropped, e.local_name().as_ref()) => {}
+            Ok(Event::Empty(e)) if depth == 1 => {
+                let prefix = root_prefix.as_deref();
+                insert_app_elements_before(
+                    &mut writer,
+                    &mut optional,
+                    prefix,
+                    e.local_name().as_ref(),
+                )?;
+                // Expand e.g. <Company/> only when it gains a value, so an
+                // unchanged app.xml is written back byte for byte
+                match mark_seen(&mut optional, e.local_name().as_ref()) {
+                    Some(value) if !value.is_empty() => {
+                        writer.write_event(Event::Start(e.to_owned()))?;
+                        writer.write_event(Event::Text(BytesText::new(value)))?;
+                        writer.write_event(Event::End(e.to_end()))?;
+                    }
+                    _ => writer.write_event(Event::Empty(e))?,
+                }
+            }
+            Ok(Event::Text(_)) | Ok(Event::CData(_)) | Ok(Event::GeneralRef(_))
+                if in_target_elem || dropping =>
+            {
+                // Skip the original text content of the target element
+            }
+            Ok(Event::End(_)) if dropping => {
+                dropping = false;
+                depth = depth.saturating_sub(1);
+            }
+            Ok(Event::End(e)) => {
+                if depth == 1 {
+                    // Insert missing elements just before </Properties>
+                    let prefix = root_prefix.as_deref();
+                    for (name, value, seen) in optional {
+                        if !seen && !value.is_empty() {
+                            write_text_element(&mut writer, &qualified_name(prefix, name), value)?;
+                        }
+                    }
+                    root_closed = true;
+                }
+                in_target_elem = false;
+                depth = depth.saturating_sub(1);
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Ok(Event::Decl(e)) => {
+                writer.write_event(Event::Decl(app_props.declaration(e)))?;
+            }
+            Ok(Event::Eof) => break,
+            Ok(e) => {
+                writer.write_event(e)?;
+            }
+            Err(e) => {
+                return Err(DocxMetaError::xml_at(
+                    APP_PART.name,
+                    e,
+                    &app_props.text,
+                    &reader,
+                ))
+            }
+        }
+        buf.clear();
+    }
+
+    if !root_closed {
+        return Ok((default_app_xml(metadata, kind)?, true));
+    }
+    let xml =
+        String::from_utf8(writer.into_inner()).map_err(|e| DocxMetaError::xml(APP_PART.name, e))?;
+    Ok((xml, false))
+}
+
+// Applies the opt-in output formatting of `options` to a generated part
+fn format_xml(part: &str, xml: String, options: &SaveOptions) -> Result<String, DocxMetaError> {
+    let mut xml = if options.indent_xml {
+        indent_xml(&xml).map_err(|e| DocxMetaError::xml(part, e))?
+    } else {
+        xml
+    };
+    if options.lf_line_endings {
+        // Parsers normalize line breaks in text to LF anyway
+        xml = xml.replace("\r\n", "\n").replace('\r', "\n");
+    }
+    Ok(xml)
+}
+
+// Re-writes `xml` one element per line, dropping the whitespace-only text
+// that separated elements before. Values are left exactly as they were.
+fn indent_xml(xml: &str) -> Result<String, String> {
+    let (bom, body) = match xml.strip_prefix('\u{feff}') {
+        Some(body) => ("\u{feff}", body),
+        None => ("", xml),
+    };
+    let mut reader = Reader::from_str(body);
+    let mut writer = Writer::new_with_indent(bom.as_bytes().to_vec(), b' ', 2);
+    // Whitespace is only dropped between elements: next to a reference or
+    // CDATA it is part of a value, as in "R&amp; &lt;D&gt;"
+    let mut after_text = false;
+    let mut pending_space = None;
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        let is_text = matches!(
+            event,
+            Event::Text(_) | Event::GeneralRef(_) | Event::CData(_)
+        );
+        let event = match event {
+            Event::Text(e) if !after_text && e.iter().all(u8::is_ascii_whitespace) => {
+                pending_space = Some(e);
+                continue;
+            }
+            Event::Eof => break,
+            // The indenting writer would start a new line before a reference,
+            // inside the value; as escaped text it stays in place
+            Event::GeneralRef(e) => {
+                let reference = format!("&{};", e.decode().map_err(|e| e.to_string())?);
+                Event::Text(BytesText::from_escaped(reference))
+            }
+            event => event,
+        };
+        if let Some(space) = pending_space.take().filter(|_| is_text) {
+            writer
+                .write_event(Event::Text(space))
+                .map_err(|e| e.to_string())?;
+        }
+        writer.write_event(event).map_err(|e| e.to_string())?;
+        after_text = is_text;
+    }
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+// The elements a generator should leave out: those of fields that are both
+// marked for removal and empty
+fn dropped_elements(
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+    element: fn(MetadataField) -> Option<&'static str>,
+) -> Vec<&'static str> {
+    options
+        .remove_fields
+        .iter()
+        .filter(|field| metadata.get(**field).is_empty())
+        .filter_map(|field| element(*field))
+        .collect()
+}
+
+fn is_dropped(dropped: &[&str], name: &[u8]) -> bool {
+    dropped.iter().any(|dropped| dropped.as_bytes() == name)
+}
+
+// The core.xml element holding a field, by qualified name
+fn core_element(field: MetadataField) -> Option<&'static str> {
+    match field {
+        MetadataField::Created => Some("dcterms:created"),
+        MetadataField::Modified => Some("dcterms:modified"),
+        MetadataField::LastPrinted => Some("cp:lastPrinted"),
+        MetadataField::Revision => Some("cp:revision"),
+        MetadataField::ContentStatus => Some("cp:contentStatus"),
+        MetadataField::Description => Some("dc:description"),
+        MetadataField::Keywords => Some("cp:keywords"),
+        MetadataField::Language => Some("dc:language"),
+        MetadataField::Category => Some("cp:category"),
+        MetadataField::Version => Some("cp:version"),
+        MetadataField::Identifier => Some("dc:identifier"),
+        _ => None,
+    }
+}
+
+// The app.xml element holding a field, by local name
+fn app_element(field: MetadataField) -> Option<&'static str> {
+    match field {
+        MetadataField::Template => Some("Template"),
+        MetadataField::Application => Some("Application"),
+        MetadataField::AppVersion => Some("AppVersion"),
+        MetadataField::Company => Some("Company"),
+        MetadataField::Manager => Some("Manager"),
+        MetadataField::TotalTime => Some("TotalTime"),
+        MetadataField::Pages => Some("Pages"),
+        MetadataField::Words => Some("Words"),
+        MetadataField::Characters => Some("Characters"),
+        MetadataField::DocSecurity => Some("DocSecurity"),
+        MetadataField::ScaleCrop => Some("ScaleCrop"),
+        MetadataField::LinksUpToDate => Some("LinksUpToDate"),
+        MetadataField::SharedDoc => Some("SharedDoc"),
+        _ => None,
+    }
+}
+
+// The app.xml elements we edit, by local name, in APP_ELEMENT_ORDER
+fn app_fields(metadata: &CoreMetadata) -> [(&'static str, &str); 13] {
+    [
+        ("Template", &metadata.template),
+        ("TotalTime", &metadata.total_time),
+        ("Pages", &metadata.pages),
+        ("Words", &metadata.words),
+        ("Characters", &metadata.characters),
+        ("Application", &metadata.application),
+        ("DocSecurity", &metadata.doc_security),
+        ("ScaleCrop", &metadata.scale_crop),
+        ("Manager", &metadata.manager),
+        ("Company", &metadata.company),
+        ("LinksUpToDate", &metadata.links_up_to_date),
+        ("SharedDoc", &metadata.shared_doc),
+        ("AppVersion", &metadata.app_version),
+    ]
+}
+
+// The order Word writes app.xml elements in. The schema doesn't require
+// one, but inserted elements follow it so the part still reads naturally.
+const APP_ELEMENT_ORDER: [&str; 20] = [
+    "Template",
+    "TotalTime",
+    "Pages",
+    "Words",
+    "Characters",
+    "Application",
+    "DocSecurity",
+    "Lines",
+    "Paragraphs",
+    "ScaleCrop",
+    "HeadingPairs",
+    "TitlesOfParts",
+    "Manager",
+    "Company",
+    "LinksUpToDate",
+    "CharactersWithSpaces",
+    "SharedDoc",
+    "HyperlinkBase",
+    "HyperlinksChanged",
+    "AppVersion",
+];
+
+fn app_element_rank(name: &[u8]) -> Option<usize> {
+    APP_ELEMENT_ORDER
+        .iter()
+        .position(|known| known.as_bytes() == name)
+}
+
+// Local names of the root's children, so a rewrite knows up front which
+// elements it will meet
+fn child_names(xml: &str) -> Vec<Vec<u8>> {
+    let mut reader = Reader::from_str(xml);
+    let mut depth = 0usize;
+    let mut names = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                if depth == 2 {
+                    names.push(e.local_name().as_ref().to_vec());
+                }
+            }
+            Ok(Event::Empty(e)) if depth == 1 => names.push(e.local_name().as_ref().to_vec()),
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) | Err(_) => break,
+            _ => (),
+        }
+    }
+    names
+}
+
+// Writes the missing elements that APP_ELEMENT_ORDER puts before `next`,
+// marking them as seen
+fn insert_app_elements_before<W: Write>(
+    writer: &mut Writer<W>,
+    optional: &mut [(&str, &str, bool)],
+    prefix: Option<&str>,
+    next: &[u8],
+) -> io::Result<()> {
+    let Some(next_rank) = app_element_rank(next) else {
+        return Ok(());
+    };
+    for (name, value, seen) in optional.iter_mut() {
+        let before = app_element_rank(name.as_bytes()).is_some_and(|rank| rank < next_rank);
+        if !*seen && !value.is_empty() && before {
+            write_text_element(writer, &qualified_name(prefix, name), value)?;
+            *seen = true;
+        }
+    }
+    Ok(())
+}
+
+// Looks `name` up in a generator's optional element table, marking it as
+// present in the original part and returning its new value.
+fn mark_seen<'a>(optional: &mut [(&str, &'a str, bool)], name: &[u8]) -> Option<&'a str> {
+    let (_, value, seen) = optional
+        .iter_mut()
+        .find(|(optional_name, _, _)| optional_name.as_bytes() == name)?;
+    *seen = true;
+    Some(*value)
+}
+
+// Whether the part's dcterms elements carry xsi:type="dcterms:W3CDTF".
+// Generators differ; the first dcterms child decides, and a part without
+// any gets the type, as Word writes it.
+fn uses_w3cdtf_type(xml: &str) -> bool {
+    let mut reader = Reader::from_str(xml);
+    let mut depth = 0usize;
+    loop {
+        let element = match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                (depth == 2).then_some(e)
+            }
+            Ok(Event::Empty(e)) if depth == 1 => Some(e),
+            Ok(Event::End(_)) => {
+                depth = depth.saturating_sub(1);
+                None
+            }
+            Ok(Event::Eof) | Err(_) => return true,
+            _ => None,
+        };
+        if let Some(e) = element {
+            if e.name().as_ref().starts_with(b"dcterms:") {
+                return matches!(e.try_get_attribute("xsi:type"), Ok(Some(_)));
+            }
+        }
+    }
+}
+
+// The namespaces a core.xml root declares, by their customary prefix
+const CORE_NAMESPACES: [(&str, &str); 5] = [
+    (
+        "cp",
+        "http://schemas.openxmlformats.org/package/2006/metadata/core-properties",
+    ),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("dcmitype", "http://purl.org/dc/dcmitype/"),
+    ("xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+];
+
+// Adds an xmlns declaration for the prefix of `name` (e.g. "dc" for
+// "dc:language") from `namespaces` unless `root` already has one. A part
+// with no dc:* elements may well leave the dc prefix unbound.
+fn declare_namespace(root: &mut BytesStart, name: &str, namespaces: &[(&str, &str)]) {
+    let Some((prefix, _)) = name.split_once(':') else {
+        return;
+    };
+    let Some((_, namespace)) = namespaces.iter().find(|(known, _)| *known == prefix) else {
+        return;
+    };
+    let attribute = format!("xmlns:{}", prefix);
+    if let Ok(None) = root.try_get_attribute(attribute.as_str()) {
+        root.push_attribute((attribute.as_str(), *namespace));
+    }
+}
+
+// --- OpenDocument Text ---
+// An .odt is a zip package too, but keeps its metadata in a single meta.xml,
+// under <office:meta> and with its own element names. Fields ODF has no
+// place for (content status, category, company, the flags...) are neither
+// read nor written. Nor does ODF use empty elements, as an empty date or
+// count doesn't validate: clearing a field removes its element.
+
+const ODT_MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+const ODF_MIMETYPE_PREFIX: &str = "application/vnd.oasis.opendocument.";
+const ODT_META_PART: &str = "meta.xml";
+const ODT_MANIFEST: &str = "META-INF/manifest.xml";
+const ODT_SIGNATURES: &str = "META-INF/documentsignatures.xml";
+
+// The namespaces a meta.xml root declares, by their customary prefix
+const ODT_NAMESPACES: [(&str, &str); 3] = [
+    ("office", "urn:oasis:names:tc:opendocument:xmlns:office:1.0"),
+    ("meta", "urn:oasis:names:tc:opendocument:xmlns:meta:1.0"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+];
+
+const ODT_AUTHOR_ELEMENTS: [&str; 2] = ["meta:initial-creator", "dc:creator"];
+
+// The fields meta.xml holds
+const ODT_FIELDS: [MetadataField; 12] = [
+    MetadataField::Created,
+    MetadataField::Modified,
+    MetadataField::LastPrinted,
+    MetadataField::Revision,
+    MetadataField::Description,
+    MetadataField::Keywords,
+    MetadataField::Language,
+    MetadataField::Application,
+    MetadataField::TotalTime,
+    MetadataField::Pages,
+    MetadataField::Words,
+    MetadataField::Characters,
+];
+
+// The counts are attributes of the one meta:document-statistic element
+const ODT_STATISTICS: [(MetadataField, &str); 3] = [
+    (MetadataField::Pages, "meta:page-count"),
+    (MetadataField::Words, "meta:word-count"),
+    (MetadataField::Characters, "meta:character-count"),
+];
+
+// The meta.xml element holding a field, by qualified name. Keywords are
+// stored one meta:keyword element each, and the counts as ODT_STATISTICS.
+fn odt_element(field: MetadataField) -> Option<&'static str> {
+    match field {
+        MetadataField::Created => Some("meta:creation-date"),
+        MetadataField::Modified => Some("dc:date"),
+        MetadataField::LastPrinted => Some("meta:print-date"),
+        MetadataField::Revision => Some("meta:editing-cycles"),
+        MetadataField::Description => Some("dc:description"),
+        MetadataField::Language => Some("dc:language"),
+        MetadataField::Application => Some("meta:generator"),
+        MetadataField::TotalTime => Some("meta:editing-duration"),
+        _ => None,
+    }
+}
+
+// The format an ODF package's leading "mimetype" entry names; None for
+// packages without one
+fn open_document_mimetype<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    let mut mimetype = String::new();
+    archive
+        .by_name("mimetype")
+        .ok()?
+        .take(256)
+        .read_to_string(&mut mimetype)
+        .ok()?;
+    Some(mimetype.trim().to_string())
+}
+
+fn is_open_document_text<R: Read + Seek>(archive: &mut ZipArchive<R>) -> bool {
+    open_document_mimetype(archive).as_deref() == Some(ODT_MIMETYPE)
+}
+
+// A package without meta.xml loads as all-empty fields; the next save
+// creates the part.
+fn load_odt_meta<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    metadata: &mut CoreMetadata,
+) -> Result<(), DocxMetaError> {
+    match read_xml_part(archive, ODT_META_PART)? {
+        Some(meta) => parse_odt_meta(&meta.text, metadata),
+        None => Ok(()),
+    }
+}
+
+fn parse_odt_meta(xml: &str, metadata: &mut CoreMetadata) -> Result<(), DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut seen: Vec<MetadataField> = Vec::new();
+    let mut keywords = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.name().as_ref() == b"meta:document-statistic" =>
+            {
+                for (field, attribute) in ODT_STATISTICS {
+                    if let Ok(Some(value)) = e.try_get_attribute(attribute) {
+                        *metadata.get_mut(field) =
+                            String::from_utf8_lossy(&value.value).into_owned();
+                    }
+                }
+            }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"meta:keyword" => {
+                let keyword = read_value(&mut reader)
+                    .map_err(|e| DocxMetaError::xml_at(ODT_META_PART, e, xml, &reader))?;
+                keywords.push(keyword);
+            }
+            Ok(Event::Start(ref e)) => {
+                let field = MetadataField::ALL.into_iter().find(|field| {
+                    odt_element(*field).is_some_and(|name| name.as_bytes() == e.name().as_ref())
+                });
+                if let Some(field) = field {
+                    let value = read_value(&mut reader)
+                        .map_err(|e| DocxMetaError::xml_at(ODT_META_PART, e, xml, &reader))?;
+                    // A duplicated element keeps its first value, the one
+                    // odt_meta_xml replaces
+                    if !seen.contains(&field) {
+                        *metadata.get_mut(field) = match field {
+                            MetadataField::TotalTime => {
+                                odt_duration_minutes(&value).unwrap_or(value)
+                            }
+                            MetadataField::Created
+                            | MetadataField::Modified
+                            | MetadataField::LastPrinted => odt_date(&value),
+                            _ => value,
+                        };
+                        seen.push(field);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(ODT_META_PART, e, xml, &reader)),
+            _ => (),
+        }
+        buf.clear();
+    }
+    if !keywords.is_empty() {
+        metadata.keywords = keywords.join(", ");
+    }
+    Ok(())
+}
+
+// ODF dates are often written without an offset, in a local time the file
+// doesn't record; those load as UTC. Anything else is kept as written.
+fn odt_date(value: &str) -> String {
+    let trimmed = value.trim();
+    if parse_date_with_offset(trimmed).is_none() {
+        if let Ok(date) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S%.f") {
+            return date.and_utc().to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        }
+    }
+    value.to_string()
+}
+
+// meta:editing-duration is an xsd:duration such as "PT2H5M30S", while the
+// field holds whole minutes like app.xml's TotalTime. Durations counted in
+// months or years have no fixed length and give None.
+fn odt_duration_minutes(value: &str) -> Option<String> {
+    let value = value.trim().strip_prefix('P')?;
+    let (days, time) = value.split_once('T').unwrap_or((value, ""));
+    let mut seconds = 0.0;
+    for (part, units) in [
+        (days, &[('D', 86400.0)][..]),
+        (time, &[('H', 3600.0), ('M', 60.0), ('S', 1.0)][..]),
+    ] {
+        let mut rest = part;
+        for (unit, length) in units {
+            if let Some((amount, tail)) = rest.split_once(*unit) {
+                seconds += amount.parse::<f64>().ok()? * length;
+                rest = tail;
+            }
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(((seconds / 60.0) as u64).to_string())
+}
+
+// Empty for an empty or unparsable count of minutes
+fn odt_duration(minutes: &str) -> String {
+    minutes
+        .trim()
+        .parse::<u64>()
+        .map(|minutes| format!("PT{}H{}M0S", minutes / 60, minutes % 60))
+        .unwrap_or_default()
+}
+
+// meta:editing-duration as the part has it; None when absent
+fn odt_written_duration(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) if e.name().as_ref() == b"meta:editing-duration" => {
+                return read_value(&mut reader).ok();
+            }
+            Event::Eof => return None,
+            _ => (),
+        }
+    }
+}
+
+// Whether saving changes any value meta.xml holds; an unchanged part is
+// copied as it is, like the OOXML property parts
+fn odt_meta_changed(meta: &XmlPart, metadata: &CoreMetadata) -> Result<bool, DocxMetaError> {
+    let mut before = CoreMetadata::default();
+    parse_odt_meta(&meta.text, &mut before)?;
+    Ok(ODT_FIELDS
+        .into_iter()
+        .any(|field| before.get(field) != metadata.get(field)))
+}
+
+// The edited meta.xml, from the original part or, without one, from an
+// empty <office:meta>
+fn odt_meta_xml(
+    meta: Option<&XmlPart>,
+    metadata: &CoreMetadata,
+    options: &SaveOptions,
+) -> Result<String, DocxMetaError> {
+    let default_meta;
+    let meta = match meta {
+        Some(meta) => meta,
+        None => {
+            default_meta = default_odt_meta();
+            &default_meta
+        }
+    };
+    let mut reader = Reader::from_str(&meta.text);
+    let mut writer = Writer::new(meta.output_prefix());
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut in_target_elem = false;
+    let mut dropping = false;
+    // (qualified name, new value, seen in the original), as in core_xml
+    // An unedited duration keeps the seconds the field can't hold
+    let duration = odt_written_duration(&meta.text)
+        .filter(|written| {
+            odt_duration_minutes(written).as_deref() == Some(metadata.total_time.trim())
+        })
+        .unwrap_or_else(|| odt_duration(&metadata.total_time));
+    let mut values: Vec<(&str, &str, bool)> = MetadataField::ALL
+        .into_iter()
+        .filter_map(|field| {
+            let value = match field {
+                MetadataField::TotalTime => duration.as_str(),
+                field => metadata.get(field),
+            };
+            odt_element(field).map(|name| (name, value, false))
+        })
+        .collect();
+    let keywords = split_keywords(&metadata.keywords);
+    let mut keywords_written = false;
+    let mut statistics_seen = false;
+    let blanked: &[&str] = if options.anonymize {
+        &["dc:title", "meta:initial-creator", "dc:creator"]
+    } else if options.clear_last_modified_by {
+        &["dc:creator"]
+    } else {
+        &[]
+    };
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if depth == 0 => {
+                depth += 1;
+                // The root must bind the prefix of every element we may insert
+                let mut start = e.to_owned();
+                for (name, _, _) in &values {
+                    declare_namespace(&mut start, name, &ODT_NAMESPACES);
+                }
+                writer.write_event(Event::Start(start))?;
+            }
+            Ok(Event::Start(e))
+                if depth == 2 && e.name().as_ref() == b"meta:document-statistic" =>
+            {
+                depth += 1;
+                statistics_seen = true;
+                writer.write_event(Event::Start(odt_statistics(&e, metadata)))?;
+            }
+            Ok(Event::Empty(e))
+                if depth == 2 && e.name().as_ref() == b"meta:document-statistic" =>
+            {
+                statistics_seen = true;
+                writer.write_event(Event::Empty(odt_statistics(&e, metadata)))?;
+            }
+            Ok(Event::Start(e)) if depth == 2 && e.name().as_ref() == b"meta:keyword" => {
+                // The first keyword element is replaced by the whole list
+                depth += 1;
+                dropping = true;
+                write_odt_keywords(&mut writer, &keywords, &mut keywords_written)?;
+            }
+            Ok(Event::Empty(e)) if depth == 2 && e.name().as_ref() == b"meta:keyword" => {
+                write_odt_keywords(&mut writer, &keywords, &mut keywords_written)?;
+            }
+            Ok(Event::Start(e)) if depth == 2 => {
+                depth += 1;
+                let name = e.name();
+                let duplicate = values
+                    .iter()
+                    .any(|(known, _, seen)| *seen && known.as_bytes() == name.as_ref());
+                let value = if blanked
+                    .iter()
+                    .any(|blanked| blanked.as_bytes() == name.as_ref())
+                {
+                    Some("")
+                } else {
+                    match mark_seen(&mut values, name.as_ref()) {
+                        // A cleared field or a duplicate: leave out the element
+                        Some(value) if value.is_empty() || duplicate => {
+                            dropping = true;
+                            buf.clear();
+                            continue;
+                        }
+                        value => value,
+                    }
+                };
+                writer.write_event(Event::Start(e.to_owned()))?;
+                if let Some(value) = value {
+                    in_target_elem = true;
+                    writer.write_event(Event::Text(BytesText::new(value)))?;
+                }
+            }
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                writer.write_event(Event::Start(e))?;
+            }
+            Ok(Event::Text(_)) | Ok(Event::CData(_)) | Ok(Event::GeneralRef(_))
+                if in_target_elem || dropping => {}
+            Ok(Event::Empty(e)) if depth == 2 => {
+                let duplicate = values
+                    .iter()
+                    .any(|(known, _, seen)| *seen && known.as_bytes() == e.name().as_ref());
+                match mark_seen(&mut values, e.name().as_ref()) {
+                    Some(value) if !value.is_empty() && !duplicate => {
+                        writer.write_event(Event::Start(e.to_owned()))?;
+                        writer.write_event(Event::Text(BytesText::new(value)))?;
+                        writer.write_event(Event::End(e.to_end()))?;
+                    }
+                    Some(_) => {}
+                    None => writer.write_event(Event::Empty(e))?,
+                }
+            }
+            Ok(Event::Empty(e)) if depth == 1 && e.name().as_ref() == b"office:meta" => {
+                // Expand <office:meta/> so it can take the new elements
+                writer.write_event(Event::Start(e.to_owned()))?;
+                insert_odt_elements(
+                    &mut writer,
+                    &values,
+                    &keywords,
+                    keywords_written,
+                    statistics_seen,
+                    metadata,
+                )?;
+                writer.write_event(Event::End(e.to_end()))?;
+            }
+            Ok(Event::End(_)) if dropping => {
+                dropping = false;
+                depth = depth.saturating_sub(1);
+            }
+            Ok(Event::End(e)) => {
+                if depth == 2 {
+                    insert_odt_elements(
+                        &mut writer,
+                        &values,
+                        &keywords,
+                        keywords_written,
+                        statistics_seen,
+                        metadata,
+                    )?;
+                }
+                in_target_elem = false;
+                depth = depth.saturating_sub(1);
+                writer.write_event(Event::End(e.to_owned()))?;
+            }
+            Ok(Event::Decl(e)) => {
+                writer.write_event(Event::Decl(meta.declaration(e)))?;
+            }
+            Ok(Event::Eof) => break,
+            Ok(e) => {
+                writer.write_event(e)?;
+            }
+            Err(e) => return Err(DocxMetaError::xml_at(ODT_META_PART, e, &meta.text, &reader)),
+        }
+        buf.clear();
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|e| DocxMetaError::xml(ODT_META_PART, e))
+}
+
+// Writes the keywords as meta:keyword elements, once
+fn write_odt_keywords<W: Write>(
+    writer: &mut Writer<W>,
+    keywords: &[String],
+    written: &mut bool,
+) -> io::Result<()> {
+    if !*written {
+        for keyword in keywords {
+            write_text_element(writer, "meta:keyword", keyword)?;
+        }
+        *written = true;
+    }
+    Ok(())
+}
+
+// Appends what the original <office:meta> lacked, just before its end tag
+fn insert_odt_elements<W: Write>(
+    writer: &mut Writer<W>,
+    values: &[(&str, &str, bool)],
+    keywords: &[String],
+    keywords_written: bool,
+    statistics_seen: bool,
+    metadata: &CoreMetadata,
+) -> io::Result<()> {
+    for (name, value, seen) in values {
+        if !seen && !value.is_empty() {
+            write_text_element(writer, name, value)?;
+        }
+    }
+    if !keywords_written {
+        for keyword in keywords {
+            write_text_element(writer, "meta:keyword", keyword)?;
+        }
+    }
+    let counted = ODT_STATISTICS
+        .iter()
+        .any(|(field, _)| !metadata.get(*field).is_empty());
+    if !statistics_seen && counted {
+        let statistics = odt_statistics(&BytesStart::new("meta:document-statistic"), metadata);
+        writer.write_event(Event::Empty(statistics))?;
+    }
+    Ok(())
+}
+
+// meta:document-statistic with the page, word and character counts set and
+// its other counts (tables, images...) kept, in their original order
+fn odt_statistics(original: &BytesStart, metadata: &CoreMetadata) -> BytesStart<'static> {
+    let mut statistics = original.to_owned();
+    statistics.clear_attributes();
+    let mut written = Vec::new();
+    for attribute in original.attributes().flatten() {
+        let statistic = ODT_STATISTICS
+            .iter()
+            .find(|(_, name)| name.as_bytes() == attribute.key.as_ref());
+        match statistic {
+            Some((field, name)) => {
+                written.push(*field);
+                let value = metadata.get(*field);
+                if !value.is_empty() {
+                    statistics.push_attribute((*name, value));
+                }
+            }
+            None => statistics.push_attribute(attribute),
+        }
+    }
+    for (field, name) in ODT_STATISTICS {
+        let value = metadata.get(field);
+        if !written.contains(&field) && !value.is_empty() {
+            statistics.push_attribute((name, value));
+        }
+    }
+    statistics
+}
+
+// Where a save starts from when the package has no meta.xml
+fn default_odt_meta() -> XmlPart {
+    let namespaces: String = ODT_NAMESPACES
+        .iter()
+        .map(|(prefix, namespace)| format!(" xmlns:{}=\"{}\"", prefix, namespace))
+        .collect();
+    XmlPart {
+        text: format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<office:document-meta{} office:version=\"1.2\"><office:meta></office:meta></office:document-meta>",
+            namespaces
+        ),
+        utf8_bom: false,
+        transcoded: false,
+    }
+}
+
+// Lists meta.xml in the manifest, which names every file of an ODF package
+fn add_manifest_entry(xml: &[u8]) -> Result<Vec<u8>, quick_xml::Error> {
+    if attribute_values(xml, b"file-entry", "manifest:full-path")?
+        .iter()
+        .any(|path| path == ODT_META_PART)
+    {
+        return Ok(xml.to_vec());
+    }
+    let mut entry = BytesStart::new("manifest:file-entry");
+    entry.push_attribute(("manifest:full-path", ODT_META_PART));
+    entry.push_attribute(("manifest:media-type", "text/xml"));
+    insert_before_root_end(xml, entry)
+}
+
+// --- File Timestamps ---
+
+// Sets the filesystem modified time to dcterms:modified and, when asked and
+// the platform supports it, the creation time to dcterms:created. Linux has
+// no settable birth time, so there the creation time is left alone.
+pub fn sync_file_times(
+    path: &Path,
+    metadata: &CoreMetadata,
+    include_created: bool,
+) -> Result<(), DocxMetaError> {
+    let modified = parse_date(&metadata.modified).ok_or_else(|| DocxMetaError::DateParse {
+        field: MetadataField::Modified,
+        value: metadata.modified.clone(),
+    })?;
+    let mut times = FileTimes::new().set_modified(SystemTime::from(modified));
+    if include_created {
+        if let Some(created) = parse_date(&metadata.created) {
+            times = with_created_time(times, SystemTime::from(created));
+        }
+    }
+    let file = File::options().write(true).open(path)?;
+    Ok(file.set_times(times)?)
+}
+
+#[cfg(windows)]
+fn with_created_time(times: FileTimes, created: SystemTime) -> FileTimes {
+    use std::os::windows::fs::FileTimesExt;
+    times.set_created(created)
+}
+
+#[cfg(target_os = "macos")]
+fn with_created_time(times: FileTimes, created: SystemTime) -> FileTimes {
+    use std::os::macos::fs::FileTimesExt;
+    times.set_created(created)
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn with_created_time(times: FileTimes, _created: SystemTime) -> FileTimes {
+    times
+}
+
+// --- Part Decoding ---
+// Property parts are normally UTF-8, but UTF-16 (with a BOM) and other
+// declared encodings do turn up. They are decoded up front so everything
+// else works on UTF-8 text; regenerated parts are always written as UTF-8.
+
+struct XmlPart {
+    text: String,
+    utf8_bom: bool,   // Re-emit the UTF-8 BOM the original had
+    transcoded: bool, // Not UTF-8 originally, so the declaration must change
+}
+
+impl XmlPart {
+    // Initial writer contents: the BOM, if the original had one
+    fn output_prefix(&self) -> Vec<u8> {
+        bom_prefix(self.utf8_bom)
+    }
+
+    // The original declaration, with its encoding switched to UTF-8 when
+    // the part was transcoded
+    fn declaration<'a>(&self, decl: BytesDecl<'a>) -> BytesDecl<'a> {
+        if !self.transcoded || decl.encoding().is_none() {
+            return decl;
+        }
+        let attribute = |value: Cow<[u8]>| String::from_utf8_lossy(&value).into_owned();
+        let version = decl.version().map(attribute).unwrap_or("1.0".to_string());
+        let standalone = decl
+            .standalone()
+            .and_then(|value| value.ok())
+            .map(attribute);
+        BytesDecl::new(&version, Some("UTF-8"), standalone.as_deref()).into_owned()
+    }
+}
+
+fn read_xml_part<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<XmlPart>, DocxMetaError> {
+    match archive.index_for_name(name) {
+        Some(index) => read_xml_entry(archive, index, name).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_xml_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    index: usize,
+    name: &str,
+) -> Result<XmlPart, DocxMetaError> {
+    let mut bytes = Vec::new();
+    archive.by_index(index)?.read_to_end(&mut bytes)?;
+    let part = decode_xml(&bytes).map_err(|encoding| DocxMetaError::Undecodable {
+        part: name.to_string(),
+        encoding,
+    })?;
+    check_closed(name, &part.text)?;
+    Ok(part)
+}
+
+// The reader takes the end of input for the end of the document even with
+// elements still open, so a part cut short would otherwise load, and save,
+// as if it were complete.
+fn check_closed(name: &str, xml: &str) -> Result<(), DocxMetaError> {
+    let mut reader = Reader::from_str(xml);
+    let mut open = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                open.push(String::from_utf8_lossy(e.name().as_ref()).into_owned())
+            }
+            Ok(Event::End(_)) => {
+                open.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(DocxMetaError::xml_at(name, e, xml, &reader)),
+            _ => (),
+        }
+    }
+    match open.pop() {
+        Some(tag) => Err(DocxMetaError::xml_at(
+            name,
+            IllFormedError::MissingEndTag(tag),
+            xml,
+            &reader,
+        )),
+        None => Ok(()),
+    }
+}
+
+// core.xml, wherever _rels/.rels puts it; None when missing
+fn read_core_part<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Option<XmlPart>, DocxMetaError> {
+    match core_part_name(archive) {
+        Some(core_name) => read_xml_part(archive, &core_name),
+        None => Ok(None),
+    }
+}
+
+fn read_property_parts<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<(Option<XmlPart>, Option<XmlPart>), DocxMetaError> {
+    Ok((
+        read_core_part(archive)?,
+        read_xml_part(archive, APP_PART.name)?,
+    ))
+}
+
+// A BOM wins over the declaration, as in the XML spec's detection rules
+fn decode_xml(bytes: &[u8]) -> Result<XmlPart, &'static str> {
+    let (encoding, bom_length) =
+        Encoding::for_bom(bytes).unwrap_or_else(|| (declared_encoding(bytes).unwrap_or(UTF_8), 0));
+    let (text, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+    if had_errors {
+        return Err(encoding.name());
+    }
+    Ok(XmlPart {
+        text: text.into_owned(),
+        utf8_bom: encoding == UTF_8 && bom_length > 0,
+        transcoded: encoding != UTF_8,
+    })
+}
+
+// The encoding named by `<?xml ... encoding="..."?>`. Without a BOM the
+// declaration was readable as ASCII, so a "UTF-16" label there is wrong
+// and ignored.
+fn declared_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    let mut reader = Reader::from_reader(bytes);
+    let mut buf = Vec::new();
+    match reader.read_event_into(&mut buf) {
+        Ok(Event::Decl(decl)) => {
+            let label = decl.encoding()?.ok()?;
+            Encoding::for_label(&label).filter(|encoding| ![UTF_16LE, UTF_16BE].contains(encoding))
+        }
+        _ => None,
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// The reader silently skips a UTF-8 BOM, so seed the output with it when
+// the source part had one.
+fn bom_prefix(had_bom: bool) -> Vec<u8> {
+    if had_bom {
+        UTF8_BOM.to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+// Fallback used when the document has no core.xml at all
+fn default_core_xml(metadata: &CoreMetadata) -> Result<String, DocxMetaError> {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    writer.write_event(Event::Decl(BytesDecl::new(
+        "1.0",
+        Some("UTF-8"),
+        Some("yes"),
+    )))?;
+    let mut root = BytesStart::new("cp:coreProperties");
+    for (prefix, namespace) in CORE_NAMESPACES {
+        root.push_attribute((format!("xmlns:{}", prefix).as_str(), namespace));
+    }
+    writer.write_event(Event::Start(root))?;
+    for (name, value) in [
+        ("dc:description", &metadata.description),
+        ("cp:keywords", &metadata.keywords),
+        ("dc:language", &metadata.language),
+        ("cp:category", &metadata.category),
+        ("cp:revision", &metadata.revision),
+        ("cp:version", &metadata.version),
+        ("dc:identifier", &metadata.identifier),
+        ("cp:contentStatus", &metadata.content_status),
+        ("cp:lastPrinted", &metadata.last_printed),
+    ] {
+        if !value.is_empty() {
+            write_text_element(&mut writer, name, value)?;
+        }
+    }
+    for (name, value) in [
+        ("dcterms:created", &metadata.created),
+        ("dcterms:modified", &metadata.modified),
+    ] {
+        if !value.is_empty() {
+            write_date_element(&mut writer, name, value, true)?;
+        }
+    }
+    writer.write_event(Event::End(BytesEnd::new("cp:coreProperties")))?;
+    String::from_utf8(writer.into_inner().into_inner())
+        .map_err(|e| DocxMetaError::xml(CORE_PART.name, e))
+}
+
+// Fallback used when the document has no app.xml at all: the elements `kind`
+// uses, with its usual <Application> unless one was given
+fn default_app_xml(metadata: &CoreMetadata, kind: DocumentKind) -> Result<String, DocxMetaError> {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    writer.write_event(Event::Decl(BytesDecl::new(
+        "1.0",
+        Some("UTF-8"),
+        Some("yes"),
+    )))?;
+    let mut root = BytesStart::new("Properties");
+    root.push_attribute((
+        "xmlns",
+        "http://schemas.openxmlformats.org/officeDocument/2006/extended-properties",
+    ));
+    root.push_attribute((
+        "xmlns:vt",
+        "http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes",
+    ));
+    writer.write_event(Event::Start(root))?;
+    for (name, value) in app_fields(metadata) {
+        let value = match kind.default_application() {
+            Some(application) if name == "Application" && value.is_empty() => application,
+            _ => value,
+        };
+        if !value.is_empty() && kind.uses_app_element(name) {
+            write_text_element(&mut writer, name, value)?;
+        }
+    }
+    writer.write_event(Event::End(BytesEnd::new("Properties")))?;
+    String::from_utf8(writer.into_inner().into_inner())
+        .map_err(|e| DocxMetaError::xml(APP_PART.name, e))
+}
+
+// --- Package Registration ---
+// A part we create from scratch is only found by Office once the package
+// lists its content type and links it from _rels/.rels.
+
+const CONTENT_TYPES: &str = "[Content_Types].xml";
+const PACKAGE_RELS: &str = "_rels/.rels";
+const RELATIONSHIPS_CONTENT_TYPE: &str = "application/vnd.openxmlformats-package.relationships+xml";
+const OFFICE_DOCUMENT_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument";
+
+struct PackagePart {
+    name: &'static str,
+    content_type: &'static str,
+    relationship_type: &'static str,
+}
+
+const CORE_PART: PackagePart = PackagePart {
+    name: "docProps/core.xml",
+    content_type: "application/vnd.openxmlformats-package.core-properties+xml",
+    relationship_type:
+        "http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties",
+};
+
+const CUSTOM_PART: PackagePart = PackagePart {
+    name: "docProps/custom.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.custom-properties+xml",
+    relationship_type:
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/custom-properties",
+};
+
+// A preview of the first page that some generators embed, usually as
+// docProps/thumbnail.jpeg. Its content type mostly comes from a Default for
+// the extension, which other images share, so only an Override is removed.
+const THUMBNAIL_PREFIX: &str = "docProps/thumbnail.";
+const THUMBNAIL_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships/metadata/thumbnail";
+
+fn thumbnail_part<R: Read + Seek>(archive: &ZipArchive<R>) -> Option<String> {
+    archive
+        .file_names()
+        .find(|name| {
+            name.get(..THUMBNAIL_PREFIX.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(THUMBNAIL_PREFIX))
+        })
+        .map(str::to_string)
+}
+
+const APP_PART: PackagePart = PackagePart {
+    name: "docProps/app.xml",
+    content_type: "application/vnd.openxmlformats-officedocument.extended-properties+xml",
+    relationship_type:
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties",
+};
+
+fn add_content_type_override(xml: &[u8], part: &PackagePart) -> Result<Vec<u8>, quick_xml::Error> {
+    let part_name = format!("/{}", part.name);
+    if attribute_values(xml, b"Override", "PartName")?.contains(&part_name) {
+        return Ok(xml.to_vec());
+    }
+    let mut entry = BytesStart::new("Override");
+    entry.push_attribute(("PartName", part_name.as_str()));
+    entry.push_attribute(("ContentType", part.content_type));
+    insert_before_root_end(xml, entry)
+}
+
+// The zip entry of the core properties part, as _rels/.rels links it.
+// Part names are case-insensitive while entry names are not, so it is
+// matched ignoring case. Falls back to docProps/core.xml when the
+// relationships can't be read or don't point at an existing entry.
+fn core_part_name<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    let linked = read_xml_part(archive, PACKAGE_RELS)
+        .ok()
+        .flatten()
+        .and_then(|rels| core_relationship_target(&rels.text));
+    let name = linked
+        .iter()
+        .map(String::as_str)
+        .chain([CORE_PART.name])
+        .find_map(|name| {
+            archive
+                .file_names()
+                .find(|entry| entry.eq_ignore_ascii_case(name))
+                .map(str::to_string)
+        });
+    name
+}
+
+// Some producers write the relationship type with "officedocument" in
+// lower case, a leftover from a draft of the standard
+const CORE_RELATIONSHIP_TYPES: [&str; 2] = [
+    CORE_PART.relationship_type,
+    "http://schemas.openxmlformats.org/officedocument/2006/relationships/metadata/core-properties",
+];
+
+fn core_relationship_target(rels: &str) -> Option<String> {
+    let mut reader = Reader::from_str(rels);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                let attribute = |name: &str| {
+                    e.try_get_attribute(name)
+                        .ok()
+                        .flatten()
+                        .map(|value| String::from_utf8_lossy(&value.value).into_owned())
+                };
+                if attribute("Type")
+                    .is_some_and(|kind| CORE_RELATIONSHIP_TYPES.contains(&kind.as_str()))
+                {
+                    // Package-level targets are relative to the package root
+                    return attribute("Target")
+                        .map(|target| target.trim_start_matches('/').to_string());
+                }
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => (),
+        }
+    }
+}
+
+fn add_content_type_default(
+    xml: &[u8],
+    extension: &str,
+    content_type: &str,
+) -> Result<Vec<u8>, quick_xml::Error> {
+    let extensions = attribute_values(xml, b"Default", "Extension")?;
+    if extensions
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension))
+    {
+        return Ok(xml.to_vec());
+    }
+    let mut entry = BytesStart::new("Default");
+    entry.push_attribute(("Extension", extension));
+    entry.push_attribute(("ContentType", content_type));
+    insert_before_root_end(xml, entry)
+}
+
+// A _rels/.rels linking just the main part, for a package that has none
+fn default_package_rels(main_part: &str) -> Result<Vec<u8>, quick_xml::Error> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Event::Decl(BytesDecl::new(
+        "1.0",
+        Some("UTF-8"),
+        Some("yes"),
+    )))?;
+    let mut root = BytesStart::new("Relationships");
+    root.push_attribute((
+        "xmlns",
+        "http://schemas.openxmlformats.org/package/2006/relationships",
+    ));
+    writer.write_event(Event::Start(root))?;
+    let mut entry = BytesStart::new("Relationship");
+    entry.push_attribute(("Id", "rId1"));
+    entry.push_attribute(("Type", OFFICE_DOCUMENT_RELATIONSHIP));
+    entry.push_attribute(("Target", main_part));
+    writer.write_event(Event::Empty(entry))?;
+    writer.write_event(Event::End(BytesEnd::new("Relationships")))?;
+    Ok(writer.into_inner())
+}
+
+fn add_package_relationship(xml: &[u8], part: &PackagePart) -> Result<Vec<u8>, quick_xml::Error> {
+    let targets = attribute_values(xml, b"Relationship", "Target")?;
+    if targets
+        .iter()
+        .any(|target| target.trim_start_matches('/') == part.name)
+    {
+        return Ok(xml.to_vec());
+    }
+    let ids = attribute_values(xml, b"Relationship", "Id")?;
+    let id = (1..)
+        .map(|n| format!("rId{}", n))
+        .find(|id| !ids.contains(id))
+        .unwrap_or_default();
+    let mut entry = BytesStart::new("Relationship");
+    entry.push_attribute(("Id", id.as_str()));
+    entry.push_attribute(("Type", part.relationship_type));
+    entry.push_attribute(("Target", part.name));
+    insert_before_root_end(xml, entry)
+}
+
+// Values of `attribute` on every element named `local_name`
+fn attribute_values(
+    xml: &[u8],
+    local_name: &[u8],
+    attribute: &str,
+) -> Result<Vec<String>, quick_xml::Error> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut values = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == local_name => {
+                if let Ok(Some(value)) = e.try_get_attribute(attribute) {
+                    values.push(String::from_utf8_lossy(&value.value).into_owned());
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(e),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(values)
+}
+
+fn remove_content_type_override(xml: &[u8], part_name: &str) -> Result<Vec<u8>, quick_xml::Error> {
+    let part_name = format!("/{}", part_name);
+    remove_elements(xml, b"Override", "PartName", |name| {
+        name.eq_ignore_ascii_case(&part_name)
+    })
+}
+
+fn remove_package_relationship(
+    xml: &[u8],
+    relationship_type: &str,
+) -> Result<Vec<u8>, quick_xml::Error> {
+    remove_elements(xml, b"Relationship", "Type", |kind| {
+        kind == relationship_type
+    })
+}
+
+// Copies `xml` without the `local_name` elements whose `attribute` satisfies
+// `matches`, children included
+fn remove_elements(
+    xml: &[u8],
+    local_name: &[u8],
+    attribute: &str,
+    matches: impl Fn(&str) -> bool,
+) -> Result<Vec<u8>, quick_xml::Error> {
+    let is_removed = |e: &BytesStart| {
+        e.local_name().as_ref() == local_name
+            && e.try_get_attribute(attribute)
+                .ok()
+                .flatten()
+                .is_some_and(|value| matches(&String::from_utf8_lossy(&value.value)))
+    };
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(bom_prefix(xml.starts_with(UTF8_BOM)));
+    let mut buf = Vec::new();
+    let mut removing = 0usize; // Depth inside a removed element
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(_)) if removing > 0 => removing += 1,
+            Ok(Event::End(_)) if removing > 0 => removing -= 1,
+            Ok(_) if removing > 0 => (),
+            Ok(Event::Start(e)) if is_removed(&e) => removing = 1,
+            Ok(Event::Empty(e)) if is_removed(&e) => (),
+            Ok(e) => writer.write_event(e)?,
+            Err(e) => return Err(e),
+        }
+        buf.clear();
+    }
+    Ok(writer.into_inner())
+}
+
+fn insert_before_root_end(xml: &[u8], entry: BytesStart) -> Result<Vec<u8>, quick_xml::Error> {
+    let mut reader = Reader::from_reader(xml);
+    let mut writer = Writer::new(bom_prefix(xml.starts_with(UTF8_BOM)));
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                writer.write_event(Event::Start(e))?;
+            }
+            Ok(Event::End(e)) => {
+                if depth == 1 {
+                    writer.write_event(Event::Empty(entry.borrow()))?;
+                }
+                depth = depth.saturating_sub(1);
+                writer.write_event(Event::End(e))?;
+            }
+            Ok(Event::Eof) => break,
+            Ok(e) => writer.write_event(e)?,
+            Err(e) => return Err(e),
+        }
+        buf.clear();
+    }
+    Ok(writer.into_inner())
+}
+
+fn write_text_element<W: Write>(writer: &mut Writer<W>, name: &str, value: &str) -> io::Result<()> {
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    writer.write_event(Event::Text(BytesText::new(value)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))
+}
+
+fn write_date_element<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    value: &str,
+    typed: bool,
+) -> io::Result<()> {
+    let mut start = BytesStart::new(name);
+    if typed {
+        start.push_attribute(("xsi:type", "dcterms:W3CDTF"));
+    }
+    writer.write_event(Event::Start(start))?;
+    writer.write_event(Event::Text(BytesText::new(value)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))
+}
+
+fn qualified_name(prefix: Option<&str>, local: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{}:{}", prefix, local),
+        None => local.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // --- Fixtures ---
+    // Packages are built in memory and only touch the disk to go through
+    // load_metadata and save_metadata, in a temp file of their own.
+
+    const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/><Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/></Types>"#;
+
+    const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+    const DOCUMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body><w:p><w:r><w:t>Hello</w:t></w:r></w:p></w:body></w:document>"#;
+
+    const CORE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:dcmitype="http://purl.org/dc/dcmitype/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>Quarterly Report</dc:title><dc:creator>Alice</dc:creator><cp:lastModifiedBy>Bob</cp:lastModifiedBy><cp:revision>3</cp:revision><dcterms:created xsi:type="dcterms:W3CDTF">2020-01-01T09:00:00Z</dcterms:created><dcterms:modified xsi:type="dcterms:W3CDTF">2020-02-01T17:30:00Z</dcterms:modified></cp:coreProperties>"#;
+
+    const APP_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes"><Template>Normal.dotm</Template><TotalTime>5</TotalTime><Pages>1</Pages><Words>10</Words><Characters>60</Characters><Application>Microsoft Office Word</Application><DocSecurity>0</DocSecurity><Lines>1</Lines><Paragraphs>1</Paragraphs><ScaleCrop>false</ScaleCrop><HeadingPairs><vt:vector size="2" baseType="variant"><vt:variant><vt:lpstr>Title</vt:lpstr></vt:variant><vt:variant><vt:i4>1</vt:i4></vt:variant></vt:vector></HeadingPairs><TitlesOfParts><vt:vector size="1" baseType="lpstr"><vt:lpstr>Quarterly Report</vt:lpstr></vt:vector></TitlesOfParts><Company>Contoso</Company><LinksUpToDate>false</LinksUpToDate><CharactersWithSpaces>69</CharactersWithSpaces><SharedDoc>false</SharedDoc><HyperlinksChanged>false</HyperlinksChanged><AppVersion>16.0000</AppVersion></Properties>"#;
+
+    // A core.xml as some third-party tools write it: single quotes, the
+    // namespaces declared in an unusual order alongside ones Office never
+    // uses, no xsi binding (so untyped dates) and a foreign element.
+    const ODD_NAMESPACES_CORE_XML: &str = r#"<?xml version='1.0' encoding='UTF-8'?>
+<cp:coreProperties xmlns:dcterms='http://purl.org/dc/terms/' xmlns:x='urn:example:extra' xmlns:dc='http://purl.org/dc/elements/1.1/' xmlns:cp='http://schemas.openxmlformats.org/package/2006/metadata/core-properties' x:origin='scanner'><dc:title>Scanned</dc:title><x:reviewer x:role='lead'>Carol</x:reviewer><dcterms:created>2021-03-04T05:06:07Z</dcterms:created><dcterms:modified>2021-03-05T05:06:07Z</dcterms:modified></cp:coreProperties>"#;
+
+    const ODT_MANIFEST_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2"><manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.text"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/><manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/></manifest:manifest>"#;
+
+    const ODT_CONTENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2"><office:body><office:text><text:p>Hello</text:p></office:text></office:body></office:document-content>"#;
+
+    // As LibreOffice writes it: local dates without an offset, the editing
+    // time as a duration and the counts as attributes
+    const ODT_META_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" office:version="1.2"><office:meta><meta:creation-date>2020-01-01T09:00:00</meta:creation-date><dc:date>2020-02-01T17:30:00</dc:date><meta:editing-cycles>3</meta:editing-cycles><meta:editing-duration>PT1H5M30S</meta:editing-duration><meta:generator>LibreOffice/7.6</meta:generator><meta:keyword>alpha</meta:keyword><meta:keyword>beta</meta:keyword><meta:document-statistic meta:page-count="2" meta:word-count="10" meta:character-count="60"/><dc:title>Minutes</dc:title></office:meta></office:document-meta>"#;
+
+    // The entries of a package, in archive order
+    #[derive(Clone)]
+    struct Fixture {
+        parts: Vec<(String, Vec<u8>)>,
+    }
+
+    impl Fixture {
+        // A minimal Word document with both property parts
+        fn docx() -> Self {
+            Fixture { parts: Vec::new() }
+                .with(CONTENT_TYPES, CONTENT_TYPES_XML)
+                .with(PACKAGE_RELS, RELS_XML)
+                .with("word/document.xml", DOCUMENT_XML)
+                .with(CORE_PART.name, CORE_XML)
+                .with(APP_PART.name, APP_XML)
+        }
+
+        fn docx_without_app() -> Self {
+            Fixture::docx().without_part(&APP_PART)
+        }
+
+        fn docx_without_core() -> Self {
+            Fixture::docx().without_part(&CORE_PART)
+        }
+
+        fn docx_with_odd_namespaces() -> Self {
+            Fixture::docx().with(CORE_PART.name, ODD_NAMESPACES_CORE_XML)
+        }
+
+        // A minimal OpenDocument text, its mimetype entry first
+        fn odt() -> Self {
+            Fixture { parts: Vec::new() }
+                .with("mimetype", ODT_MIMETYPE)
+                .with(ODT_MANIFEST, ODT_MANIFEST_XML)
+                .with("content.xml", ODT_CONTENT_XML)
+                .with(ODT_META_PART, ODT_META_XML)
+        }
+
+        // Adds the part, or replaces it in place if the package has it
+        fn with(mut self, name: &str, content: impl AsRef<[u8]>) -> Self {
+            let content = content.as_ref().to_vec();
+            match self.parts.iter_mut().find(|(part, _)| part == name) {
+                Some((_, existing)) => *existing = content,
+                None => self.parts.push((name.to_string(), content)),
+            }
+            self
+        }
+
+        fn without(mut self, name: &str) -> Self {
+            self.parts.retain(|(part, _)| part != name);
+            self
+        }
+
+        // Drops the part along with its override and relationship
+        fn without_part(self, part: &PackagePart) -> Self {
+            let content_types =
+                remove_content_type_override(self.raw_part(CONTENT_TYPES).unwrap(), part.name)
+                    .unwrap();
+            let rels = remove_package_relationship(
+                self.raw_part(PACKAGE_RELS).unwrap(),
+                part.relationship_type,
+            )
+            .unwrap();
+            self.without(part.name)
+                .with(CONTENT_TYPES, content_types)
+                .with(PACKAGE_RELS, rels)
+        }
+
+        fn names(&self) -> Vec<&str> {
+            self.parts.iter().map(|(name, _)| name.as_str()).collect()
+        }
+
+        fn raw_part(&self, name: &str) -> Option<&[u8]> {
+            self.parts
+                .iter()
+                .find(|(part, _)| part == name)
+                .map(|(_, content)| content.as_slice())
+        }
+
+        fn part(&self, name: &str) -> Option<String> {
+            self.raw_part(name)
+                .map(|content| String::from_utf8(content.to_vec()).unwrap())
+        }
+
+        fn bytes(&self) -> Vec<u8> {
+            self.bytes_with(FileOptions::<()>::default())
+        }
+
+        // With every entry written with `options`, e.g. another timestamp
+        fn bytes_with(&self, options: FileOptions<()>) -> Vec<u8> {
+            let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+            for (name, content) in &self.parts {
+                zip.start_file(name.as_str(), options).unwrap();
+                zip.write_all(content).unwrap();
+            }
+            zip.finish().unwrap().into_inner()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Self {
+            let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+            let mut parts = Vec::new();
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index(index).unwrap();
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).unwrap();
+                parts.push((entry.name().to_string(), content));
+            }
+            Fixture { parts }
+        }
+
+        fn file(&self) -> TempFile {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "docx-meta-update-test-{}-{}.docx",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::write(&path, self.bytes()).unwrap();
+            TempFile(path)
+        }
+    }
+
+    // Removed again when dropped, even by a failing test
+    struct TempFile(PathBuf);
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn load(fixture: &Fixture) -> Result<CoreMetadata, DocxMetaError> {
+        load_metadata(&fixture.file().0)
+    }
+
+    // Saves in place, as the GUI does, and returns the package written
+    fn save(
+        fixture: &Fixture,
+        metadata: &CoreMetadata,
+        options: &SaveOptions,
+    ) -> Result<Fixture, DocxMetaError> {
+        let file = fixture.file();
+        save_metadata(&file.0, &file.0, metadata, options)?;
+        Ok(Fixture::from_bytes(&fs::read(&file.0)?))
+    }
+
+    // Loads the fixture, applies `edit` and saves with the default options
+    fn edit(fixture: &Fixture, edit: impl FnOnce(&mut CoreMetadata)) -> Fixture {
+        let mut metadata = load(fixture).unwrap();
+        edit(&mut metadata);
+        save(fixture, &metadata, &SaveOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn loads_core_and_app_properties() {
+        let metadata = load(&Fixture::docx()).unwrap();
+        assert_eq!(metadata.created, "2020-01-01T09:00:00Z");
+        assert_eq!(metadata.modified, "2020-02-01T17:30:00Z");
+        assert_eq!(metadata.revision, "3");
+        assert_eq!(metadata.template, "Normal.dotm");
+        assert_eq!(metadata.pages, "1");
+        assert_eq!(metadata.company, "Contoso");
+        assert_eq!(metadata.app_version, "16.0000");
+    }
+
+    #[test]
+    fn loads_from_memory_like_from_a_file() {
+        let fixture = Fixture::docx();
+        let from_reader = load_metadata_from_reader(Cursor::new(fixture.bytes())).unwrap();
+        assert_eq!(from_reader, load(&fixture).unwrap());
+    }
+
+    #[test]
+    fn saved_edits_load_back() {
+        let fixture = Fixture::docx();
+        let saved = edit(&fixture, |metadata| {
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+            metadata.keywords = "alpha, beta".to_string();
+            metadata.pages = "12".to_string();
+        });
+        let metadata = load(&saved).unwrap();
+        assert_eq!(metadata.modified, "2024-05-06T07:08:09Z");
+        assert_eq!(metadata.keywords, "alpha, beta");
+        assert_eq!(metadata.pages, "12");
+        assert_eq!(saved.names(), fixture.names());
+    }
+
+    #[test]
+    fn saving_to_a_writer_matches_saving_a_file() {
+        let fixture = Fixture::docx();
+        let mut metadata = load(&fixture).unwrap();
+        metadata.description = "In memory".to_string();
+        let mut output = Cursor::new(Vec::new());
+        let options = SaveOptions::default();
+        save_metadata_to_writer(
+            Cursor::new(fixture.bytes()),
+            &mut output,
+            &metadata,
+            &options,
+        )
+        .unwrap();
+        let in_memory = Fixture::from_bytes(output.get_ref());
+        let on_disk = save(&fixture, &metadata, &options).unwrap();
+        assert_eq!(in_memory.parts, on_disk.parts);
+    }
+
+    #[test]
+    fn loads_without_app_xml() {
+        let metadata = load(&Fixture::docx_without_app()).unwrap();
+        assert_eq!(metadata.revision, "3");
+        assert_eq!(metadata.template, "");
+        assert_eq!(metadata.application, "");
+    }
+
+    #[test]
+    fn foreign_elements_and_namespaces_survive_an_edit() {
+        let fixture = Fixture::docx_with_odd_namespaces();
+        let saved = edit(&fixture, |metadata| {
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+            metadata.description = "Inserted".to_string();
+        });
+        let expected = ODD_NAMESPACES_CORE_XML
+            .replace(
+                "<dcterms:modified>2021-03-05T05:06:07Z",
+                "<dcterms:modified>2024-05-06T07:08:09Z",
+            )
+            .replace(
+                "</cp:coreProperties>",
+                "<dc:description>Inserted</dc:description></cp:coreProperties>",
+            );
+        assert_eq!(saved.part(CORE_PART.name).unwrap(), expected);
+    }
+
+    #[test]
+    fn unchanged_app_xml_is_rewritten_byte_for_byte() {
+        let metadata = load(&Fixture::docx()).unwrap();
+        let app_props = decode_xml(APP_XML.as_bytes()).unwrap();
+        let options = SaveOptions::default();
+        let (xml, rebuilt) =
+            rewrite_app_xml(Some(&app_props), &metadata, DocumentKind::Word, &options).unwrap();
+        assert_eq!(xml, APP_XML);
+        assert!(!rebuilt);
+    }
+
+    #[test]
+    fn app_xml_vectors_survive_an_edit() {
+        let saved = edit(&Fixture::docx(), |metadata| {
+            metadata.company = "Fabrikam".to_string();
+            metadata.pages = "7".to_string();
+        });
+        let app = saved.part(APP_PART.name).unwrap();
+        for vector in [
+            r#"<HeadingPairs><vt:vector size="2" baseType="variant"><vt:variant><vt:lpstr>Title</vt:lpstr></vt:variant><vt:variant><vt:i4>1</vt:i4></vt:variant></vt:vector></HeadingPairs>"#,
+            r#"<TitlesOfParts><vt:vector size="1" baseType="lpstr"><vt:lpstr>Quarterly Report</vt:lpstr></vt:vector></TitlesOfParts>"#,
+        ] {
+            assert!(app.contains(vector), "{} missing from {}", vector, app);
+        }
+        let expected = APP_XML
+            .replace("<Pages>1<", "<Pages>7<")
+            .replace("<Company>Contoso<", "<Company>Fabrikam<");
+        assert_eq!(app, expected);
+    }
+
+    #[test]
+    fn declaration_survives_a_no_change_save() {
+        let saved = edit(&Fixture::docx(), |_| {});
+        let core = saved.part(CORE_PART.name).unwrap();
+        assert!(core.starts_with(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#));
+        assert_eq!(core, CORE_XML);
+    }
+
+    #[test]
+    fn transcoded_core_xml_declares_utf8() {
+        let xml = CORE_XML
+            .replace(r#"encoding="UTF-8""#, r#"encoding="windows-1252""#)
+            .replace("Quarterly Report", "Café Report");
+        let (latin1, _, _) = encoding_rs::WINDOWS_1252.encode(&xml);
+        let saved = edit(&Fixture::docx().with(CORE_PART.name, latin1), |metadata| {
+            metadata.revision = "4".to_string();
+        });
+        let core = saved.part(CORE_PART.name).unwrap();
+        assert!(core.starts_with(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#));
+        assert!(core.contains("<dc:title>Café Report</dc:title>"));
+    }
+
+    #[test]
+    fn missing_core_xml_loads_empty_and_is_created_on_save() {
+        let fixture = Fixture::docx_without_core();
+        assert!(!fixture.part(CONTENT_TYPES).unwrap().contains("core.xml"));
+        assert!(!fixture.part(PACKAGE_RELS).unwrap().contains("core.xml"));
+        let metadata = load(&fixture).unwrap();
+        assert_eq!(metadata.created, "");
+        assert_eq!(metadata.modified, "");
+        assert_eq!(metadata.revision, "");
+        assert_eq!(metadata.template, "Normal.dotm");
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.created = "2023-01-02T03:04:05Z".to_string();
+            metadata.modified = "2023-01-02T03:04:05Z".to_string();
+            metadata.revision = "1".to_string();
+        });
+        let core = saved.part(CORE_PART.name).unwrap();
+        assert!(core.contains("<cp:revision>1</cp:revision>"));
+        assert!(saved
+            .part(CONTENT_TYPES)
+            .unwrap()
+            .contains(r#"PartName="/docProps/core.xml""#));
+        assert!(saved
+            .part(PACKAGE_RELS)
+            .unwrap()
+            .contains(r#"Target="docProps/core.xml""#));
+        let reloaded = load(&saved).unwrap();
+        assert_eq!(reloaded.created, "2023-01-02T03:04:05Z");
+        assert_eq!(reloaded.revision, "1");
+    }
+
+    #[test]
+    fn core_xml_with_a_bom_round_trips() {
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), CORE_XML.as_bytes()].concat();
+        let fixture = Fixture::docx().with(CORE_PART.name, &with_bom);
+        let metadata = load(&fixture).unwrap();
+        assert_eq!(metadata.revision, "3");
+
+        let unchanged = save(&fixture, &metadata, &SaveOptions::default()).unwrap();
+        assert_eq!(unchanged.raw_part(CORE_PART.name).unwrap(), with_bom);
+
+        let saved = edit(&fixture, |metadata| metadata.revision = "4".to_string());
+        let core = saved.raw_part(CORE_PART.name).unwrap();
+        assert!(core.starts_with(b"\xEF\xBB\xBF<?xml"));
+        assert_eq!(load(&saved).unwrap().revision, "4");
+    }
+
+    #[test]
+    fn category_and_version_are_inserted_when_absent() {
+        let fixture = Fixture::docx();
+        let metadata = load(&fixture).unwrap();
+        assert_eq!(
+            (metadata.category.as_str(), metadata.version.as_str()),
+            ("", "")
+        );
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.category = "Legal".to_string();
+            metadata.version = "2.1".to_string();
+        });
+        let core = saved.part(CORE_PART.name).unwrap();
+        assert!(core.ends_with(
+            "<cp:category>Legal</cp:category><cp:version>2.1</cp:version></cp:coreProperties>"
+        ));
+        let reloaded = load(&saved).unwrap();
+        assert_eq!(reloaded.category, "Legal");
+        assert_eq!(reloaded.version, "2.1");
+    }
+
+    #[test]
+    fn category_and_version_are_replaced_when_present() {
+        let core = CORE_XML.replace(
+            "<cp:revision>",
+            "<cp:category>Finance</cp:category><cp:version>1.0</cp:version><cp:revision>",
+        );
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        let metadata = load(&fixture).unwrap();
+        assert_eq!(metadata.category, "Finance");
+        assert_eq!(metadata.version, "1.0");
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.category = "Legal".to_string();
+            metadata.version = "2.1".to_string();
+        });
+        let expected = core
+            .replace(">Finance<", ">Legal<")
+            .replace(">1.0<", ">2.1<");
+        assert_eq!(saved.part(CORE_PART.name).unwrap(), expected);
+    }
+
+    #[test]
+    fn entities_and_cdata_round_trip() {
+        let core = CORE_XML
+            .replace("Quarterly Report", "R&amp;D Report")
+            .replace(
+                "<cp:revision>",
+                "<dc:description>R&amp;D Report</dc:description><cp:keywords><![CDATA[budget & <draft>]]></cp:keywords><cp:revision>",
+            );
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        let metadata = load(&fixture).unwrap();
+        assert_eq!(metadata.description, "R&D Report");
+        assert_eq!(metadata.keywords, "budget & <draft>");
+
+        let saved = edit(&fixture, |metadata| metadata.revision = "4".to_string());
+        let written = saved.part(CORE_PART.name).unwrap();
+        assert!(written.contains("<dc:title>R&amp;D Report</dc:title>"));
+        assert!(written.contains("<dc:description>R&amp;D Report</dc:description>"));
+        let reloaded = load(&saved).unwrap();
+        assert_eq!(reloaded.description, "R&D Report");
+        assert_eq!(reloaded.keywords, "budget & <draft>");
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.keywords = "R&D <2024>".to_string()
+        });
+        assert!(saved
+            .part(CORE_PART.name)
+            .unwrap()
+            .contains("<cp:keywords>R&amp;D &lt;2024&gt;</cp:keywords>"));
+        assert_eq!(load(&saved).unwrap().keywords, "R&D <2024>");
+    }
+
+    #[test]
+    fn other_entries_are_copied_byte_for_byte() {
+        let fixture = Fixture::docx()
+            .with("word/styles.xml", DOCUMENT_XML.repeat(20))
+            .with(
+                "word/media/image1.png",
+                (0..=255u8).cycle().take(4096).collect::<Vec<_>>(),
+            );
+        let original = fixture.bytes();
+        let file = fixture.file();
+        let mut metadata = load_metadata(&file.0).unwrap();
+        metadata.created = "2019-01-01T00:00:00Z".to_string();
+        metadata.modified = "2024-05-06T07:08:09Z".to_string();
+        save_metadata(&file.0, &file.0, &metadata, &SaveOptions::default()).unwrap();
+
+        let raw_entries = |bytes: Vec<u8>| {
+            let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+            let mut entries = Vec::new();
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index_raw(index).unwrap();
+                if [CORE_PART.name, APP_PART.name].contains(&entry.name()) {
+                    continue;
+                }
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).unwrap();
+                entries.push((
+                    entry.name().to_string(),
+                    entry.compression(),
+                    entry.crc32(),
+                    data,
+                ));
+            }
+            entries
+        };
+        let before = raw_entries(original);
+        assert_eq!(before.len(), 5);
+        assert_eq!(raw_entries(fs::read(&file.0).unwrap()), before);
+    }
+
+    #[test]
+    fn duplicated_modified_keeps_only_the_first_copy() {
+        let stale = r#"<dcterms:modified xsi:type="dcterms:W3CDTF">2019-09-09T09:09:09Z</dcterms:modified>"#;
+        let core = CORE_XML.replace(
+            "</cp:coreProperties>",
+            &format!("{}{}</cp:coreProperties>", stale, stale),
+        );
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        assert_eq!(load(&fixture).unwrap().modified, "2020-02-01T17:30:00Z");
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+        });
+        let expected = CORE_XML.replace("2020-02-01T17:30:00Z", "2024-05-06T07:08:09Z");
+        assert_eq!(saved.part(CORE_PART.name).unwrap(), expected);
+    }
+
+    #[test]
+    fn bidi_text_round_trips_byte_exact() {
+        let title = "التقرير الفصلي Q3 \u{200F}2024 (مسودة)";
+        let core = CORE_XML.replace("Quarterly Report", title);
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        let description = "ملخص \u{202B}English inside\u{202C} نهاية";
+        let saved = edit(&fixture, |metadata| {
+            metadata.description = description.to_string();
+        });
+        let expected = core.replace(
+            "</cp:coreProperties>",
+            &format!(
+                "<dc:description>{}</dc:description></cp:coreProperties>",
+                description
+            ),
+        );
+        assert_eq!(saved.raw_part(CORE_PART.name).unwrap(), expected.as_bytes());
+        assert_eq!(load(&saved).unwrap().description, description);
+    }
+
+    #[test]
+    fn package_without_doc_props_gains_registered_parts() {
+        let fixture = Fixture::docx()
+            .without_part(&CORE_PART)
+            .without_part(&APP_PART);
+        assert!(fixture
+            .names()
+            .iter()
+            .all(|name| !name.starts_with("docProps/")));
+        assert_eq!(load(&fixture).unwrap(), CoreMetadata::default());
+
+        let saved = edit(&fixture, |metadata| {
+            metadata.created = "2023-01-02T03:04:05Z".to_string();
+            metadata.modified = "2023-01-02T03:04:05Z".to_string();
+            metadata.pages = "2".to_string();
+        });
+        assert!(saved.part(CORE_PART.name).is_some());
+        assert!(saved.part(APP_PART.name).is_some());
+        let content_types = saved.part(CONTENT_TYPES).unwrap();
+        let rels = saved.part(PACKAGE_RELS).unwrap();
+        for part in [&CORE_PART, &APP_PART] {
+            assert!(content_types.contains(&format!(
+                r#"PartName="/{}" ContentType="{}""#,
+                part.name, part.content_type
+            )));
+            assert!(rels.contains(&format!(
+                r#"Type="{}" Target="{}""#,
+                part.relationship_type, part.name
+            )));
+        }
+        let reloaded = load(&saved).unwrap();
+        assert_eq!(reloaded.created, "2023-01-02T03:04:05Z");
+        assert_eq!(reloaded.pages, "2");
+    }
+
+    #[test]
+    fn macro_enabled_document_loads_and_saves() {
+        let content_types = CONTENT_TYPES_XML.replace(
+            "wordprocessingml.document.main+xml",
+            "ms-word.document.macroEnabled.main+xml",
+        );
+        let vba_project = b"\xD0\xCF\x11\xE0 not really a macro project".as_slice();
+        let fixture = Fixture::docx()
+            .with(CONTENT_TYPES, &content_types)
+            .with("word/vbaProject.bin", vba_project);
+        assert_eq!(
+            document_kind(&fixture.file().0).unwrap(),
+            DocumentKind::Word
+        );
+        assert_eq!(load(&fixture).unwrap().revision, "3");
+
+        let saved = edit(&fixture, |metadata| metadata.revision = "4".to_string());
+        verify_document(&saved.file().0).unwrap();
+        assert_eq!(load(&saved).unwrap().revision, "4");
+        assert_eq!(saved.part(CONTENT_TYPES).unwrap(), content_types);
+        assert_eq!(saved.raw_part("word/vbaProject.bin").unwrap(), vba_project);
+    }
+
+    #[test]
+    fn inserted_dates_follow_the_typed_style() {
+        let core = CORE_XML.replace(
+            r#"<dcterms:modified xsi:type="dcterms:W3CDTF">2020-02-01T17:30:00Z</dcterms:modified>"#,
+            "",
+        );
+        let saved = edit(&Fixture::docx().with(CORE_PART.name, core), |metadata| {
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+        });
+        assert!(saved.part(CORE_PART.name).unwrap().ends_with(
+            r#"<dcterms:modified xsi:type="dcterms:W3CDTF">2024-05-06T07:08:09Z</dcterms:modified></cp:coreProperties>"#
+        ));
+    }
+
+    #[test]
+    fn inserted_dates_follow_the_untyped_style() {
+        let core = ODD_NAMESPACES_CORE_XML.replace(
+            "<dcterms:modified>2021-03-05T05:06:07Z</dcterms:modified>",
+            "",
+        );
+        let saved = edit(&Fixture::docx().with(CORE_PART.name, core), |metadata| {
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+        });
+        let written = saved.part(CORE_PART.name).unwrap();
+        assert!(written.ends_with(
+            "<dcterms:modified>2024-05-06T07:08:09Z</dcterms:modified></cp:coreProperties>"
+        ));
+        assert!(!written.contains("xsi"));
+    }
+
+    #[test]
+    fn inserted_dates_default_to_typed() {
+        let core = CORE_XML
+            .replace(
+                r#"<dcterms:created xsi:type="dcterms:W3CDTF">2020-01-01T09:00:00Z</dcterms:created>"#,
+                "",
+            )
+            .replace(
+                r#"<dcterms:modified xsi:type="dcterms:W3CDTF">2020-02-01T17:30:00Z</dcterms:modified>"#,
+                "",
+            )
+            .replace(r#" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance""#, "");
+        let saved = edit(&Fixture::docx().with(CORE_PART.name, core), |metadata| {
+            metadata.created = "2024-05-06T07:08:09Z".to_string();
+            metadata.modified = "2024-05-07T07:08:09Z".to_string();
+        });
+        let written = saved.part(CORE_PART.name).unwrap();
+        assert!(written.contains(r#" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance""#));
+        assert!(written.ends_with(concat!(
+            r#"<dcterms:created xsi:type="dcterms:W3CDTF">2024-05-06T07:08:09Z</dcterms:created>"#,
+            r#"<dcterms:modified xsi:type="dcterms:W3CDTF">2024-05-07T07:08:09Z</dcterms:modified>"#,
+            "</cp:coreProperties>"
+        )));
+    }
+
+    #[test]
+    fn doc_security_survives_a_no_op_save() {
+        let app = APP_XML.replace("<DocSecurity>0<", "<DocSecurity>8<");
+        let fixture = Fixture::docx().with(APP_PART.name, &app);
+        assert_eq!(load(&fixture).unwrap().doc_security, "8");
+
+        let saved = edit(&fixture, |_| {});
+        assert_eq!(saved.part(APP_PART.name).unwrap(), app);
+        let saved = edit(&fixture, |metadata| metadata.pages = "2".to_string());
+        assert!(saved
+            .part(APP_PART.name)
+            .unwrap()
+            .contains("<DocSecurity>8</DocSecurity>"));
+    }
+
+    #[test]
+    fn unchanged_date_only_created_is_kept() {
+        let core = CORE_XML.replace("2020-01-01T09:00:00Z", "2020-01-01");
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        let mut metadata = load(&fixture).unwrap();
+        assert_eq!(metadata.created, "2020-01-01");
+        metadata.revision = "4".to_string();
+        for date_profile in [DateProfile::Word, DateProfile::StrictWord] {
+            let options = SaveOptions {
+                date_profile,
+                ..SaveOptions::default()
+            };
+            let saved = save(&fixture, &metadata, &options).unwrap();
+            let written = saved.part(CORE_PART.name).unwrap();
+            assert!(written.contains(r#"W3CDTF">2020-01-01</dcterms:created>"#));
+        }
+    }
+
+    #[test]
+    fn edited_date_only_created_is_validated() {
+        let core = CORE_XML.replace("2020-01-01T09:00:00Z", "2020-01-01");
+        let fixture = Fixture::docx().with(CORE_PART.name, &core);
+        let mut metadata = load(&fixture).unwrap();
+        metadata.created = "2020-01-02".to_string();
+
+        let saved = save(&fixture, &metadata, &SaveOptions::default()).unwrap();
+        assert_eq!(load(&saved).unwrap().created, "2020-01-02T00:00:00Z");
+        let strict = SaveOptions {
+            date_profile: DateProfile::StrictWord,
+            ..SaveOptions::default()
+        };
+        assert!(matches!(
+            save(&fixture, &metadata, &strict),
+            Err(DocxMetaError::NotWordDate { .. })
+        ));
+        metadata.created = "2020-13-45".to_string();
+        assert!(matches!(
+            save(&fixture, &metadata, &SaveOptions::default()),
+            Err(DocxMetaError::DateParse { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_xml_is_an_error_not_a_panic() {
+        let metadata = load(&Fixture::docx()).unwrap();
+        for (part, xml) in [(CORE_PART.name, CORE_XML), (APP_PART.name, APP_XML)] {
+            for end in (0..xml.len()).step_by(11) {
+                let Some(truncated) = xml.get(..end) else {
+                    continue;
+                };
+                let fixture = Fixture::docx().with(part, truncated);
+                // Whatever loads must save or fail with an error
+                let _ = load(&fixture);
+                let _ = save(&fixture, &metadata, &SaveOptions::default());
+            }
+        }
+
+        let fixture = Fixture::docx().with(CORE_PART.name, &CORE_XML[..CORE_XML.len() - 40]);
+        assert!(matches!(
+            load(&fixture),
+            Err(DocxMetaError::Xml {
+                position: Some(_),
+                ..
+            })
+        ));
+        assert!(save(&fixture, &metadata, &SaveOptions::default()).is_err());
+    }
+
+    #[test]
+    fn refused_values_name_their_field() {
+        let fixture = Fixture::docx();
+        let mut metadata = load(&fixture).unwrap();
+        metadata.pages = "many".to_string();
+        metadata.shared_doc = "maybe".to_string();
+        let options = SaveOptions::default();
+        assert!(matches!(
+            save(&fixture, &metadata, &options),
+            Err(DocxMetaError::InvalidCount {
+                field: MetadataField::Pages,
+                ..
+            })
+        ));
+        let problems = validate(&fixture.file().0, &metadata, &options);
+        assert!(matches!(
+            problems[..],
+            [DocxMetaError::InvalidCount {
+                field: MetadataField::Pages,
+                ..
+            }]
+        ));
+
+        let mut metadata = load(&fixture).unwrap();
+        metadata.modified = "2019-01-01T00:00:00Z".to_string();
+        let options = SaveOptions {
+            block_date_order: true,
+            ..SaveOptions::default()
+        };
+        assert!(matches!(
+            save(&fixture, &metadata, &options),
+            Err(DocxMetaError::DateOrder(
+                DateOrderIssue::ModifiedBeforeCreated
+            ))
+        ));
+    }
+
+    #[test]
+    fn packages_that_are_not_documents_are_refused() {
+        let fixture = Fixture::docx().without("word/document.xml");
+        assert!(matches!(
+            load(&fixture),
+            Err(DocxMetaError::NotADocx(NotADocxReason::NoMainPart))
+        ));
+        let fixture = Fixture::docx().without(CONTENT_TYPES);
+        assert!(matches!(
+            load(&fixture),
+            Err(DocxMetaError::MissingPart(part)) if part == CONTENT_TYPES
+        ));
+        assert!(matches!(
+            load_metadata_from_reader(Cursor::new(b"not a zip".to_vec())),
+            Err(DocxMetaError::NotADocx(NotADocxReason::Unreadable(_)))
+        ));
+        // A zip with content types and relationships but no main part, like
+        // a NuGet or VSIX package, must not be given property parts
+        let fixture = Fixture::docx()
+            .without("word/document.xml")
+            .without(CORE_PART.name)
+            .without(APP_PART.name)
+            .with("readme.txt", "Not a document");
+        let file = fixture.file();
+        let metadata = CoreMetadata {
+            company: "Clobbered".to_string(),
+            ..CoreMetadata::default()
+        };
+        assert!(matches!(
+            save_metadata(&file.0, &file.0, &metadata, &SaveOptions::default()),
+            Err(DocxMetaError::NotADocx(NotADocxReason::NoMainPart))
+        ));
+        assert_eq!(fs::read(&file.0).unwrap(), fixture.bytes());
+    }
+
+    #[test]
+    fn cancelled_save_leaves_the_file_alone() {
+        let fixture = Fixture::docx();
+        let file = fixture.file();
+        let mut metadata = load_metadata(&file.0).unwrap();
+        metadata.revision = "4".to_string();
+        let result = save_metadata_with_progress(
+            &file.0,
+            &file.0,
+            &metadata,
+            &SaveOptions::default(),
+            &mut |_| false,
+        );
+        assert!(matches!(result, Err(DocxMetaError::Cancelled)));
+        assert_eq!(fs::read(&file.0).unwrap(), fixture.bytes());
+        let mut temp_path = file.0.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        assert!(!Path::new(&temp_path).exists());
+    }
+
+    #[test]
+    fn verified_save_keeps_an_existing_backup() {
+        let fixture = Fixture::docx();
+        let file = fixture.file();
+        let mut backup_path = file.0.as_os_str().to_owned();
+        backup_path.push(".bak");
+        let backup = TempFile(PathBuf::from(backup_path));
+        fs::write(&backup.0, "the user's backup").unwrap();
+        let mut metadata = load_metadata(&file.0).unwrap();
+        metadata.revision = "4".to_string();
+        let options = SaveOptions {
+            verify: true,
+            ..SaveOptions::default()
+        };
+        save_metadata(&file.0, &file.0, &metadata, &options).unwrap();
+        assert_eq!(load_metadata(&file.0).unwrap().revision, "4");
+        assert_eq!(fs::read_to_string(&backup.0).unwrap(), "the user's backup");
+        let mut own_backup = file.0.as_os_str().to_owned();
+        own_backup.push(".1.bak");
+        assert!(!Path::new(&own_backup).exists());
+    }
+
+    #[test]
+    fn saving_leaves_a_file_named_like_the_temp_file_alone() {
+        let fixture = Fixture::docx();
+        let file = fixture.file();
+        let unrelated = TempFile(file.0.with_extension("tmp"));
+        let mut taken = file.0.as_os_str().to_owned();
+        taken.push(".tmp");
+        let taken = TempFile(PathBuf::from(taken));
+        for other in [&unrelated, &taken] {
+            fs::write(&other.0, "keep me").unwrap();
+        }
+        let mut metadata = load_metadata(&file.0).unwrap();
+        metadata.revision = "4".to_string();
+        save_metadata(&file.0, &file.0, &metadata, &SaveOptions::default()).unwrap();
+        assert_eq!(load_metadata(&file.0).unwrap().revision, "4");
+        for other in [&unrelated, &taken] {
+            assert_eq!(fs::read_to_string(&other.0).unwrap(), "keep me");
+        }
+    }
+
+    #[test]
+    fn saved_metadata_matches_what_a_save_writes() {
+        let fixture = Fixture::docx().with(
+            CORE_PART.name,
+            CORE_XML.replace("2020-01-01T09:00:00Z", "2020-01-01"),
+        );
+        let file = fixture.file();
+        let mut metadata = load_metadata(&file.0).unwrap();
+        metadata.modified = "2024-05-06T07:08:09+02:00".to_string();
+        metadata.revision = " 4 ".to_string();
+        let options = SaveOptions::default();
+        let preview = saved_metadata(&file.0, &metadata, &options).unwrap();
+        assert_eq!(preview.created, "2020-01-01");
+        assert_eq!(preview.modified, "2024-05-06T05:08:09Z");
+        assert_eq!(preview.revision, "4");
+        let pending = generate_core_xml(&file.0, &preview, &options).unwrap();
+        let saved = save(&fixture, &metadata, &options).unwrap();
+        assert_eq!(saved.part(CORE_PART.name).unwrap(), pending);
+    }
+
+    #[test]
+    fn csv_quotes_commas_quotes_and_line_breaks() {
+        let record = MetadataRecord {
+            path: PathBuf::from("plain.docx"),
+            authors: Authors {
+                creator: "Smith, Jane".to_string(),
+                last_modified_by: "Bob \"the Builder\"".to_string(),
+            },
+            metadata: CoreMetadata {
+                description: "First line\nSecond line".to_string(),
+                ..CoreMetadata::default()
+            },
+        };
+        let csv = records_to_csv(&[record]);
+        let (header, line) = csv.split_once("\r\n").unwrap();
+        assert!(header.starts_with("path,dc:creator,cp:lastModifiedBy,"));
+        assert!(line.starts_with("plain.docx,\"Smith, Jane\",\"Bob \"\"the Builder\"\"\","));
+        assert!(line.contains(",\"First line\nSecond line\","));
+        assert!(line.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn loads_odt_meta() {
+        let metadata = load(&Fixture::odt()).unwrap();
+        assert_eq!(metadata.created, "2020-01-01T09:00:00Z");
+        assert_eq!(metadata.modified, "2020-02-01T17:30:00Z");
+        assert_eq!(metadata.revision, "3");
+        assert_eq!(metadata.total_time, "65");
+        assert_eq!(metadata.application, "LibreOffice/7.6");
+        assert_eq!(metadata.keywords, "alpha, beta");
+        assert_eq!(
+            (metadata.pages.as_str(), metadata.words.as_str()),
+            ("2", "10")
+        );
+    }
+
+    #[test]
+    fn odt_dates_are_rewritten_in_place() {
+        let fixture = Fixture::odt();
+        let saved = edit(&fixture, |metadata| {
+            metadata.created = "2019-12-31T08:00:00Z".to_string();
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+        });
+        let expected = ODT_META_XML
+            .replace(
+                "<meta:creation-date>2020-01-01T09:00:00<",
+                "<meta:creation-date>2019-12-31T08:00:00Z<",
+            )
+            .replace(
+                "<dc:date>2020-02-01T17:30:00<",
+                "<dc:date>2024-05-06T07:08:09Z<",
+            );
+        assert_eq!(saved.part(ODT_META_PART).unwrap(), expected);
+        assert_eq!(saved.names(), fixture.names());
+        assert_eq!(saved.raw_part(ODT_MANIFEST), fixture.raw_part(ODT_MANIFEST));
+    }
+
+    #[test]
+    fn missing_odt_meta_is_created_and_listed() {
+        let manifest = ODT_MANIFEST_XML.replace(
+            r#"<manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>"#,
+            "",
+        );
+        let fixture = Fixture::odt()
+            .without(ODT_META_PART)
+            .with(ODT_MANIFEST, manifest);
+        assert_eq!(load(&fixture).unwrap(), CoreMetadata::default());
+        let saved = edit(&fixture, |metadata| {
+            metadata.created = "2024-05-06T07:08:09Z".to_string();
+            metadata.modified = "2024-05-06T07:08:09Z".to_string();
+            metadata.description = "Created".to_string();
+        });
+        assert!(saved.names().contains(&ODT_META_PART));
+        assert!(saved
+            .part(ODT_MANIFEST)
+            .unwrap()
+            .contains(r#"manifest:full-path="meta.xml""#));
+        assert_eq!(load(&saved).unwrap().description, "Created");
+    }
+
+    #[test]
+    fn other_open_document_formats_are_refused() {
+        let spreadsheet = "application/vnd.oasis.opendocument.spreadsheet";
+        let fixture = Fixture::odt().with("mimetype", spreadsheet);
+        assert!(matches!(
+            load(&fixture),
+            Err(DocxMetaError::NotADocx(NotADocxReason::OpenDocument(mimetype)))
+                if mimetype == spreadsheet
+        ));
+        let file = fixture.file();
+        let result = save_metadata(
+            &file.0,
+            &file.0,
+            &CoreMetadata::default(),
+            &SaveOptions::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(DocxMetaError::NotADocx(NotADocxReason::OpenDocument(_)))
+        ));
+        assert_eq!(fs::read(&file.0).unwrap(), fixture.bytes());
+    }
+
+    #[test]
+    fn reproducible_saves_are_byte_identical() {
+        let fixture = Fixture::docx();
+        let mut metadata = load(&fixture).unwrap();
+        metadata.revision = "4".to_string();
+        let options = SaveOptions {
+            reproducible_zip: true,
+            ..SaveOptions::default()
+        };
+        let dated = |year, permissions| {
+            let time = zip::DateTime::from_date_and_time(year, 6, 1, 12, 0, 0).unwrap();
+            let entries = FileOptions::<()>::default()
+                .last_modified_time(time)
+                .unix_permissions(permissions);
+            let mut output = Cursor::new(Vec::new());
+            save_metadata_to_writer(
+                Cursor::new(fixture.bytes_with(entries)),
+                &mut output,
+                &metadata,
+                &options,
+            )
+            .unwrap();
+            output.into_inner()
+        };
+        let (first, second) = (dated(2001, 0o600), dated(2023, 0o755));
+        assert_eq!(first, second);
+        assert_eq!(
+            Fixture::from_bytes(&first).part(APP_PART.name),
+            Some(APP_XML.to_string())
+        );
+    }
+
+    #[test]
+    fn loads_odd_namespace_declarations() {
+        let metadata = load(&Fixture::docx_with_odd_namespaces()).unwrap();
+        assert_eq!(metadata.created, "2021-03-04T05:06:07Z");
+        assert_eq!(metadata.modified, "2021-03-05T05:06:07Z");
+    }
+}
@@ -1,38 +1,263 @@
-use std::fs::{self, File};
-use std::io::{Cursor, Read, Write};
+mod cli;
+mod config;
+mod date_picker;
+mod i18n;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
 
-use chrono::DateTime;
+use chrono::{
+    DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc,
+};
+use docx_meta_update::{
+    date_order_issues, document_summary, dry_run, is_flag_set, is_signed, is_valid_count,
+    is_valid_doc_security, is_valid_flag, keyword_separator, load_authors, load_metadata,
+    load_record, normalize_date, parse_date_with_offset, parse_offset, records_to_csv,
+    save_metadata_with_progress, shift_date, split_keywords, validate, ChangeKind,
+    CompressionLevel, CoreMetadata, DateOrderIssue, DateProfile, DocumentKind, DocumentSummary,
+    DocxMetaError, FieldChange, FutureDatePolicy, MetadataField, NotADocxReason, PropertyParts,
+    SaveOptions, SaveProgress, SaveReport, SaveStep,
+};
 use iced::{
-    executor,
-    widget::{button, column, container, row, text, text_input},
-    Application, Command, Element, Font, Length, Settings, Theme,
+    event, executor,
+    futures::{channel::mpsc, Stream, StreamExt},
+    keyboard,
+    widget::{
+        button, checkbox, column, container, pick_list, progress_bar, row, scrollable, slider,
+        text, text_editor, text_input,
+    },
+    window, Application, Command, Element, Font, Length, Settings, Subscription, Theme,
 };
-use quick_xml::events::{BytesText, Event};
-use quick_xml::reader::Reader;
-use quick_xml::writer::Writer;
 use rfd::FileDialog;
-use zip::write::{FileOptions, ZipWriter};
-use zip::ZipArchive;
+use serde::{Deserialize, Serialize};
+
+use config::{Preferences, ThemeChoice};
+use date_picker::Calendar;
+use i18n::{fill, tr, weekdays, Language, Text};
 
 // --- Main Application Entry Point ---
 pub fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
     let mut settings = Settings::default();
     settings
         .fonts
         .push(std::borrow::Cow::from(include_bytes!("../font.ttf")));
     settings.default_font = Font::with_name("Noto Sans SC");
+    // Closing is confirmed in the app when there are unsaved edits
+    settings.window.exit_on_close_request = false;
     DocxApp::run(settings)
 }
 
 // --- Application State ---
 struct DocxApp {
     file_path: Option<PathBuf>,
-    created_date: String,
-    modified_date: String,
-    last_printed_date: String, // New field for last printed date
-    status_message: String,
+    summary: Option<DocumentSummary>, // Of the loaded file, refreshed after each save
+    metadata: CoreMetadata,           // The editable fields
+    unconfirmed_signature: bool,      // The document is signed and saving hasn't been allowed yet
+    dirty: bool,                      // Edited since the last load or save
+    confirming_revert: bool,          // "Revert" was pressed with unsaved edits
+    confirming_close: bool,           // The window was closed with unsaved edits
+    close_after_save: bool,           // "Save and close" is waiting on its save
+    description_editor: text_editor::Content, // Mirrors metadata.description
+    // How the loaded keywords were separated, for when the list shrinks to
+    // one keyword and the string no longer shows it
+    keyword_separator: &'static str,
+    new_keyword: String,    // The keyword input, added on Enter
+    status_message: String, // The latest entry of `log`, or a transient progress note
+    log: Vec<LogEntry>,
     is_loading: bool,
+    spinner_frame: usize,     // Advanced by SpinnerTick while is_loading
+    preferences: Preferences, // Saved to disk on every change
+    show_settings: bool,
+    // The folder's documents, each with whether to include it, in the order
+    // they will be saved. Set while the list is shown, before a batch starts.
+    batch_candidates: Vec<(PathBuf, bool)>,
+    batch_queue: Vec<PathBuf>,
+    batch_total: usize,
+    batch_failures: usize,
+    batch_report: Vec<String>,
+    batch_files: Vec<PathBuf>, // Every file of the last batch, for the CSV report
+    save_progress: Option<SaveProgress>, // Set while a save is running
+    cancel_save: Arc<AtomicBool>, // Shared with the running save's worker thread
+    date_picker: Option<(DateField, Calendar)>,
+    anonymize: bool, // Blank title/creator/lastModifiedBy in core.xml on the next save
+    strip_history: bool, // Blank lastModifiedBy on the next save (TotalTime is a field)
+    remove_custom: bool, // Drop docProps/custom.xml on the next save
+    confirming_remove_custom: bool,
+    remove_thumbnail: bool, // Drop docProps/thumbnail.* on the next save
+    confirming_remove_thumbnail: bool,
+    // A JSON import over fields that already have values, held until its
+    // preview is confirmed
+    pending_import: Option<MetadataSidecar>,
+    merge_import: bool, // Take only the JSON's non-null fields, keeping the rest
+    cleared_fields: Vec<MetadataField>, // Elements removed on the next save if still empty
+    reset_dates_on_anonymize: bool,
+    // Set once the first Save press has listed the changes; the next press
+    // writes them. Any edit discards it.
+    save_confirmation: Option<Vec<FieldChange>>,
+    validation: Option<Vec<String>>, // The last "Validate" result; any edit discards it
+    dry_run: bool,                   // Save only reports what would change
+    neutral_timestamp: String,
+    date_shift: String, // Offset for "Shift dates", e.g. "+30d"
+    undo_stack: VecDeque<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    recent_files: Vec<PathBuf>,
+    clipboard: Option<(PathBuf, CoreMetadata)>, // Metadata held by "Copy from..."
+    paste_fields: Vec<MetadataField>,           // Which clipboard fields a paste applies
+    show_xml_preview: bool,
+    show_xml_diff: bool,
+    show_comparison: bool,
+    compared: [Option<(PathBuf, CoreMetadata)>; 2], // The two documents side by side
+    xml_preview: XmlPreview,
+}
+
+// One line of the log panel; the status line only ever shows the latest.
+#[derive(Debug, Clone)]
+struct LogEntry {
+    time: DateTime<Local>,
+    message: String,
+    is_error: bool,
+}
+
+// Raw docProps parts for the read-only preview pane: as they are on disk,
+// and as the next save would write them (only filled while the diff is shown).
+// The package is read once per file load or opening of the pane, and the text
+// only redone when something it was made from changes.
+#[derive(Default)]
+struct XmlPreview {
+    source: Option<(PathBuf, Result<PropertyParts, DocxMetaError>)>,
+    shown_for: Option<(Language, bool, CoreMetadata, SaveOptions)>, // Diff shown as the bool
+    core: String,
+    app: String,
+    pending_core: String,
+    pending_app: String,
+}
+
+// What a save running on a worker thread reports back
+#[derive(Debug, Clone)]
+enum SaveEvent {
+    Progress(SaveProgress),
+    Finished(Result<SaveReport, Arc<DocxMetaError>>), // Shared, as messages must be Clone
+}
+
+// Likewise for a load. Opening a large package spends most of its time
+// reading the zip's central directory, before any part is read.
+#[derive(Debug, Clone)]
+enum LoadEvent {
+    Scanning,
+    ReadingProperties,
+    Finished(Box<Result<(DocumentSummary, CoreMetadata), String>>),
+}
+
+// The frames of the "working" indicator shown while is_loading
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+const SPINNER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(120);
+
+// Maximum number of edits kept for undo
+const HISTORY_LIMIT: usize = 100;
+
+// Everything a user edit can change, so undo can restore it in one step
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    metadata: CoreMetadata,
+    anonymize: bool,
+    strip_history: bool,
+    remove_custom: bool,
+    remove_thumbnail: bool,
+    cleared_fields: Vec<MetadataField>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    Created,
+    Modified,
+    LastPrinted,
+}
+
+impl DateField {
+    fn metadata_field(self) -> MetadataField {
+        match self {
+            DateField::Created => MetadataField::Created,
+            DateField::Modified => MetadataField::Modified,
+            DateField::LastPrinted => MetadataField::LastPrinted,
+        }
+    }
+}
+
+// Offered by the document language dropdown
+const COMMON_LANGUAGE_TAGS: [&str; 16] = [
+    "en-US", "en-GB", "zh-CN", "zh-TW", "ja-JP", "ko-KR", "de-DE", "fr-FR", "es-ES", "it-IT",
+    "pt-BR", "ru-RU", "nl-NL", "sv-SE", "pl-PL", "ar-SA",
+];
+
+// --- JSON Sidecar Format ---
+const METADATA_SCHEMA_VERSION: u32 = 1;
+
+// Empty fields are written as null rather than "". Unknown keys are
+// rejected, as hand-written files otherwise lose misspelled fields silently.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetadataSidecar {
+    schema_version: u32,
+    source_path: String,
+    created: Option<String>,
+    modified: Option<String>,
+    last_printed: Option<String>,
+    revision: Option<String>,
+    content_status: Option<String>,
+    description: Option<String>,
+    keywords: Option<String>,
+    language: Option<String>,
+    category: Option<String>,
+    version: Option<String>,
+    identifier: Option<String>,
+    template: Option<String>,
+    application: Option<String>,
+    app_version: Option<String>,
+    company: Option<String>,
+    manager: Option<String>,
+    total_time: Option<String>,
+    pages: Option<String>,
+    words: Option<String>,
+    characters: Option<String>,
+    doc_security: Option<String>,
+    scale_crop: Option<String>,
+    links_up_to_date: Option<String>,
+    shared_doc: Option<String>,
+}
+
+// A compression level as the dropdown lists it, in the UI language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CompressionChoice {
+    level: CompressionLevel,
+    label: &'static str,
+}
+
+impl fmt::Display for CompressionChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label)
+    }
+}
+
+// Likewise for the date profile dropdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DateProfileChoice {
+    profile: DateProfile,
+    label: &'static str,
+}
+
+impl fmt::Display for DateProfileChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label)
+    }
 }
 
 // --- Messages to update state ---
@@ -40,12 +265,187 @@ struct DocxApp {
 enum Message {
     SelectFile,
     FileSelected(Option<PathBuf>),
-    FileLoaded(Result<(String, String, String), String>), // Updated to include last printed date
+    UnsupportedFileDropped(PathBuf),
+    FileLoaded(Result<(DocumentSummary, CoreMetadata), String>),
     CreatedDateChanged(String),
     ModifiedDateChanged(String),
     LastPrintedDateChanged(String), // New message for last printed date
+    CopyDate(DateField, DateField), // From, to
+    RevisionChanged(String),
+    ContentStatusChanged(String),
+    DescriptionEdited(text_editor::Action),
+    NewKeywordChanged(String),
+    AddKeyword,
+    RemoveKeyword(usize),
+    DocumentLanguageChanged(String),
+    CategoryChanged(String),
+    VersionChanged(String),
+    IdentifierChanged(String),
+    TemplateChanged(String),
+    ApplicationChanged(String),
+    AppVersionChanged(String),
+    CompanyChanged(String),
+    ManagerChanged(String),
+    TotalTimeChanged(String),
+    PagesChanged(String),
+    WordsChanged(String),
+    CharactersChanged(String),
+    DocSecurityChanged(String),
+    FlagToggled(MetadataField, bool), // One of the app.xml flags
+    ClearField(MetadataField),
+    ConfirmEditSigned,
+    Revert,
+    ConfirmRevert,
+    CancelRevert,
+    RemoveCustomProperties,
+    ConfirmRemoveCustomProperties,
+    CancelRemoveCustomProperties,
+    RemoveThumbnail,
+    ConfirmRemoveThumbnail,
+    CancelRemoveThumbnail,
+    CloseRequested,
+    SaveAndClose,
+    ConfirmClose,
+    CancelClose,
     SaveChanges,
-    FileSaved(Result<(), String>),
+    SaveAs,
+    TouchAndSave,
+    RevealInFolder,
+    SaveAsPathSelected(Option<PathBuf>),
+    FileSavedAs(PathBuf, SaveReport),
+    SaveProgressed(SaveProgress),
+    LoadProgressed(LoadEvent),
+    SpinnerTick,
+    CancelSave,
+    FileSaved(Result<SaveReport, Arc<DocxMetaError>>),
+    SelectFolder,
+    FolderSelected(Option<PathBuf>),
+    BatchCandidateToggled(usize, bool),
+    SelectAllBatchCandidates(bool),
+    MoveBatchCandidate(usize, usize), // From, to
+    StartBatch,
+    CancelBatchSelection,
+    BatchFileSaved(PathBuf, Result<SaveReport, Arc<DocxMetaError>>),
+    ExportJson,
+    JsonExported(Result<PathBuf, String>),
+    ExportCsv,
+    CsvPathSelected(Option<PathBuf>),
+    CsvExported(Result<(PathBuf, Vec<String>), String>), // The report and the files left out
+    ImportJson,
+    JsonFileSelected(Option<PathBuf>),
+    JsonImported(Result<MetadataSidecar, ImportError>),
+    MergeImportToggled(bool),
+    ApplyImport,
+    CancelImport,
+    LanguageSelected(Language),
+    ToggleDatePicker(DateField),
+    DatePickerPreviousMonth,
+    DatePickerNextMonth,
+    DatePicked(NaiveDate),
+    Anonymize,
+    StripEditingHistory,
+    ResetDatesOnAnonymizeToggled(bool),
+    SyncFileTimesToggled(bool),
+    SyncCreatedTimeToggled(bool),
+    VerifyAfterSaveToggled(bool),
+    ConfirmBeforeSaveToggled(bool),
+    SaveSummaryReady(Result<Vec<FieldChange>, String>),
+    CancelSaveConfirmation,
+    DryRunToggled(bool),
+    BlockDateOrderToggled(bool),
+    CheckFutureDatesToggled(bool),
+    BlockFutureDatesToggled(bool),
+    FutureDateToleranceChanged(String),
+    IndentXmlToggled(bool),
+    LfLineEndingsToggled(bool),
+    StampPartsModifiedToggled(bool),
+    ReproducibleZipToggled(bool),
+    CompressionSelected(CompressionChoice),
+    DateProfileSelected(DateProfileChoice),
+    DryRunFinished(Result<Vec<FieldChange>, String>),
+    Validate,
+    Validated(Vec<String>),
+    NeutralTimestampChanged(String),
+    DateShiftChanged(String),
+    ShiftDates,
+    Undo,
+    Redo,
+    ToggleTheme,
+    ToggleSettings,
+    HighContrastToggled(bool),
+    LocalTimeDisplayToggled(bool),
+    UiScaleChanged(f32),
+    ClearLog,
+    CopyFrom,
+    CopySourceSelected(Option<PathBuf>),
+    CopySourceLoaded(PathBuf, Result<CoreMetadata, String>),
+    PasteFieldToggled(MetadataField, bool),
+    PasteInto,
+    ToggleXmlPreview,
+    ShowXmlDiffToggled(bool),
+    ToggleComparison,
+    CompareSelect(usize), // Index of the comparison slot
+    CompareFileSelected(usize, Option<PathBuf>),
+    CompareFileLoaded(usize, PathBuf, Result<CoreMetadata, String>),
+    ReadOnlyToggled(bool),
+}
+
+impl Message {
+    // What read-only mode refuses: anything that changes a field, saves, or
+    // leads to either. Checked in update, so a shortcut can't get around it.
+    fn edits_document(&self) -> bool {
+        matches!(
+            self,
+            Message::CreatedDateChanged(_)
+                | Message::ModifiedDateChanged(_)
+                | Message::LastPrintedDateChanged(_)
+                | Message::CopyDate(..)
+                | Message::RevisionChanged(_)
+                | Message::ContentStatusChanged(_)
+                | Message::DescriptionEdited(_)
+                | Message::NewKeywordChanged(_)
+                | Message::AddKeyword
+                | Message::RemoveKeyword(_)
+                | Message::DocumentLanguageChanged(_)
+                | Message::CategoryChanged(_)
+                | Message::VersionChanged(_)
+                | Message::IdentifierChanged(_)
+                | Message::TemplateChanged(_)
+                | Message::ApplicationChanged(_)
+                | Message::AppVersionChanged(_)
+                | Message::CompanyChanged(_)
+                | Message::ManagerChanged(_)
+                | Message::TotalTimeChanged(_)
+                | Message::PagesChanged(_)
+                | Message::WordsChanged(_)
+                | Message::CharactersChanged(_)
+                | Message::DocSecurityChanged(_)
+                | Message::FlagToggled(..)
+                | Message::ClearField(_)
+                | Message::RemoveCustomProperties
+                | Message::ConfirmRemoveCustomProperties
+                | Message::RemoveThumbnail
+                | Message::ConfirmRemoveThumbnail
+                | Message::SaveAndClose
+                | Message::SaveChanges
+                | Message::SaveAs
+                | Message::TouchAndSave
+                | Message::SaveAsPathSelected(_)
+                | Message::SelectFolder
+                | Message::FolderSelected(_)
+                | Message::StartBatch
+                | Message::ImportJson
+                | Message::ApplyImport
+                | Message::ToggleDatePicker(_)
+                | Message::DatePicked(_)
+                | Message::Anonymize
+                | Message::StripEditingHistory
+                | Message::ShiftDates
+                | Message::Undo
+                | Message::Redo
+                | Message::PasteInto
+        )
+    }
 }
 
 // --- Iced Application Implementation ---
@@ -56,333 +456,3589 @@ impl Application for DocxApp {
     type Flags = ();
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
+        let preferences = config::load_preferences();
         (
             Self {
                 file_path: None,
-                created_date: String::new(),
-                modified_date: String::new(),
-                last_printed_date: String::new(), // Initialize new field
-                status_message: "请选择一个 .docx 文件开始".to_string(),
+                summary: None,
+                metadata: CoreMetadata::default(),
+                unconfirmed_signature: false,
+                dirty: false,
+                confirming_revert: false,
+                confirming_close: false,
+                close_after_save: false,
+                description_editor: text_editor::Content::new(),
+                keyword_separator: ", ",
+                new_keyword: String::new(),
+                status_message: tr(preferences.language, Text::Welcome).to_string(),
+                log: Vec::new(),
                 is_loading: false,
+                spinner_frame: 0,
+                preferences,
+                show_settings: false,
+                batch_candidates: Vec::new(),
+                batch_queue: Vec::new(),
+                batch_total: 0,
+                batch_failures: 0,
+                batch_report: Vec::new(),
+                batch_files: Vec::new(),
+                save_progress: None,
+                cancel_save: Arc::new(AtomicBool::new(false)),
+                date_picker: None,
+                anonymize: false,
+                strip_history: false,
+                remove_custom: false,
+                confirming_remove_custom: false,
+                remove_thumbnail: false,
+                confirming_remove_thumbnail: false,
+                pending_import: None,
+                merge_import: true,
+                cleared_fields: Vec::new(),
+                reset_dates_on_anonymize: false,
+                save_confirmation: None,
+                validation: None,
+                dry_run: false,
+                neutral_timestamp: "2000-01-01T00:00:00Z".to_string(),
+                date_shift: String::new(),
+                undo_stack: VecDeque::new(),
+                redo_stack: Vec::new(),
+                recent_files: config::load_recent_files(),
+                clipboard: None,
+                paste_fields: MetadataField::ALL.to_vec(),
+                show_xml_preview: false,
+                show_comparison: false,
+                compared: [None, None],
+                show_xml_diff: false,
+                xml_preview: XmlPreview::default(),
             },
             Command::none(),
         )
     }
 
     fn title(&self) -> String {
-        String::from("DOCX 元数据编辑器")
+        self.t(Text::WindowTitle).to_string()
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
-        match message {
+        if self.preferences.read_only && message.edits_document() {
+            return Command::none();
+        }
+        let command = match message {
             Message::SelectFile => {
                 self.is_loading = true;
-                self.status_message = "正在打开文件对话框...".to_string();
-                Command::perform(select_file_async(), Message::FileSelected)
+                self.set_status(self.t(Text::OpeningFileDialog).to_string());
+                let filter_name = self.t(Text::DocumentFilter);
+                Command::perform(select_file_async(filter_name), Message::FileSelected)
             }
             Message::FileSelected(Some(path)) => {
                 self.is_loading = true;
-                self.status_message = format!("正在从 {} 加载元数据...", path.display());
+                self.set_status(fill(
+                    self.t(Text::LoadingFrom),
+                    &[&path.display().to_string()],
+                ));
                 self.file_path = Some(path.clone());
-                Command::perform(load_metadata(path), Message::FileLoaded)
+                self.start_load(path)
             }
             Message::FileSelected(None) => {
                 self.is_loading = false;
-                self.status_message = "文件选择已取消.".to_string();
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::UnsupportedFileDropped(path) => {
+                self.set_status(fill(
+                    self.t(Text::UnsupportedFileDropped),
+                    &[&path.display().to_string()],
+                ));
                 Command::none()
             }
-            Message::FileLoaded(Ok((created, modified, last_printed))) => {
+            Message::FileLoaded(Ok((summary, metadata))) => {
                 self.is_loading = false;
-                self.created_date = created;
-                self.modified_date = modified;
-                self.last_printed_date = last_printed; // Store last printed date
-                self.status_message = "文件加载成功.".to_string();
+                self.xml_preview = XmlPreview::default();
+                let has_property_parts = (
+                    summary.kind.is_open_document(),
+                    summary.has_core_properties,
+                    summary.has_app_properties,
+                );
+                if let Some(mismatch) = summary.parts_list_mismatch {
+                    self.log_entry(
+                        fill(
+                            self.t(Text::PartsListMismatch),
+                            &[&mismatch.counted.to_string(), &mismatch.titles.to_string()],
+                        ),
+                        true,
+                    );
+                }
+                self.summary = Some(summary);
+                self.metadata = metadata;
+                self.keyword_separator = keyword_separator(&self.metadata.keywords).unwrap_or(", ");
+                self.anonymize = false;
+                self.strip_history = false;
+                self.remove_custom = false;
+                self.remove_thumbnail = false;
+                self.cleared_fields.clear();
+                // Loads are not edits, and edits to the previous file no longer apply
+                self.dirty = false;
+                self.save_confirmation = None;
+                self.validation = None;
+                self.confirming_revert = false;
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+                let mut status = self.t(Text::FileLoaded).to_string();
+                self.unconfirmed_signature = false;
+                if let Some(path) = self.file_path.clone() {
+                    // Loading tolerates missing property parts; saving will create them
+                    match has_property_parts {
+                        (true, false, _) => {
+                            status = self.t(Text::MetaPartMissing).to_string();
+                        }
+                        (true, true, _) => {
+                            status = self.t(Text::OpenDocumentLoaded).to_string();
+                        }
+                        (false, false, false) => {
+                            status = self.t(Text::PropertyPartsMissing).to_string();
+                        }
+                        (false, false, true) => {
+                            status = self.t(Text::CorePropertiesMissing).to_string();
+                        }
+                        (false, true, false) => {
+                            status = self.t(Text::AppPropertiesMissing).to_string();
+                        }
+                        _ => {}
+                    }
+                    if let Ok(true) = is_signed(&path) {
+                        self.unconfirmed_signature = true;
+                        status = self.t(Text::SignedDocumentWarning).to_string();
+                    }
+                    config::remember_recent_file(&mut self.recent_files, path);
+                    // Best-effort: failing to persist the list shouldn't fail the load
+                    let _ = config::save_recent_files(&self.recent_files);
+                }
+                self.set_status(status);
                 Command::none()
             }
             Message::FileLoaded(Err(e)) => {
                 self.is_loading = false;
                 self.file_path = None;
-                self.created_date.clear();
-                self.modified_date.clear();
-                self.last_printed_date.clear(); // Clear last printed date on error
-                self.status_message = format!("错误: {}", e);
+                self.summary = None;
+                self.metadata = CoreMetadata::default();
+                self.unconfirmed_signature = false;
+                self.dirty = false;
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+                self.set_error(fill(self.t(Text::Error), &[&e]));
                 Command::none()
             }
             Message::CreatedDateChanged(date) => {
-                self.created_date = date;
+                self.record_edit();
+                self.set_date(DateField::Created, date);
                 Command::none()
             }
             Message::ModifiedDateChanged(date) => {
-                self.modified_date = date;
+                self.record_edit();
+                self.set_date(DateField::Modified, date);
                 Command::none()
             }
             Message::LastPrintedDateChanged(date) => {
-                self.last_printed_date = date; // Handle changes to last printed date
+                self.record_edit();
+                self.set_date(DateField::LastPrinted, date);
                 Command::none()
             }
-            Message::SaveChanges => {
-                if let Some(path) = self.file_path.clone() {
+            Message::CopyDate(from, to) => {
+                // The value as it stands in the form, edits included
+                self.record_edit();
+                *self.date_value_mut(to) = self.date_value(from).to_string();
+                Command::none()
+            }
+            Message::RevisionChanged(revision) => {
+                self.record_edit();
+                self.metadata.revision = revision;
+                Command::none()
+            }
+            Message::ContentStatusChanged(content_status) => {
+                self.record_edit();
+                self.metadata.content_status = content_status;
+                Command::none()
+            }
+            Message::ClearField(field) => {
+                self.record_edit();
+                self.metadata.get_mut(field).clear();
+                if !self.cleared_fields.contains(&field) {
+                    self.cleared_fields.push(field);
+                }
+                Command::none()
+            }
+            Message::DescriptionEdited(action) => {
+                if action.is_edit() {
+                    self.record_edit();
+                }
+                self.description_editor.perform(action);
+                self.metadata.description = editor_text(&self.description_editor);
+                Command::none()
+            }
+            Message::NewKeywordChanged(keyword) => {
+                self.new_keyword = keyword;
+                Command::none()
+            }
+            Message::AddKeyword => {
+                // Several can be typed at once, separated as in the document
+                let mut keywords = split_keywords(&self.metadata.keywords);
+                let added: Vec<String> = split_keywords(&self.new_keyword)
+                    .into_iter()
+                    .filter(|keyword| !keywords.contains(keyword))
+                    .collect();
+                self.new_keyword.clear();
+                if !added.is_empty() {
+                    self.record_edit();
+                    keywords.extend(added);
+                    self.set_keywords(keywords);
+                }
+                Command::none()
+            }
+            Message::RemoveKeyword(index) => {
+                let mut keywords = split_keywords(&self.metadata.keywords);
+                if index < keywords.len() {
+                    self.record_edit();
+                    keywords.remove(index);
+                    self.set_keywords(keywords);
+                }
+                Command::none()
+            }
+            Message::DocumentLanguageChanged(language) => {
+                self.record_edit();
+                self.metadata.language = language;
+                Command::none()
+            }
+            Message::CategoryChanged(category) => {
+                self.record_edit();
+                self.metadata.category = category;
+                Command::none()
+            }
+            Message::VersionChanged(version) => {
+                self.record_edit();
+                self.metadata.version = version;
+                Command::none()
+            }
+            Message::IdentifierChanged(identifier) => {
+                self.record_edit();
+                self.metadata.identifier = identifier;
+                Command::none()
+            }
+            Message::TemplateChanged(template) => {
+                self.record_edit();
+                self.metadata.template = template;
+                Command::none()
+            }
+            Message::ApplicationChanged(application) => {
+                self.record_edit();
+                self.metadata.application = application;
+                Command::none()
+            }
+            Message::AppVersionChanged(app_version) => {
+                self.record_edit();
+                self.metadata.app_version = app_version;
+                Command::none()
+            }
+            Message::CompanyChanged(company) => {
+                self.record_edit();
+                self.metadata.company = company;
+                Command::none()
+            }
+            Message::ManagerChanged(manager) => {
+                self.record_edit();
+                self.metadata.manager = manager;
+                Command::none()
+            }
+            Message::TotalTimeChanged(total_time) => {
+                self.record_edit();
+                self.metadata.total_time = total_time;
+                Command::none()
+            }
+            Message::PagesChanged(pages) => {
+                self.record_edit();
+                self.metadata.pages = pages;
+                Command::none()
+            }
+            Message::WordsChanged(words) => {
+                self.record_edit();
+                self.metadata.words = words;
+                Command::none()
+            }
+            Message::CharactersChanged(characters) => {
+                self.record_edit();
+                self.metadata.characters = characters;
+                Command::none()
+            }
+            Message::DocSecurityChanged(doc_security) => {
+                self.record_edit();
+                self.metadata.doc_security = doc_security;
+                Command::none()
+            }
+            Message::FlagToggled(field, enabled) => {
+                self.record_edit();
+                *self.metadata.get_mut(field) = enabled.to_string();
+                Command::none()
+            }
+            Message::ConfirmEditSigned => {
+                self.unconfirmed_signature = false;
+                Command::none()
+            }
+            Message::Revert if self.dirty => {
+                self.confirming_revert = true;
+                Command::none()
+            }
+            Message::Revert | Message::ConfirmRevert => {
+                self.confirming_revert = false;
+                let Some(path) = self.file_path.clone() else {
+                    return Command::none();
+                };
+                self.is_loading = true;
+                self.set_status(fill(
+                    self.t(Text::LoadingFrom),
+                    &[&path.display().to_string()],
+                ));
+                self.start_load(path)
+            }
+            Message::CancelRevert => {
+                self.confirming_revert = false;
+                Command::none()
+            }
+            Message::RemoveCustomProperties => {
+                self.confirming_remove_custom = true;
+                Command::none()
+            }
+            Message::ConfirmRemoveCustomProperties => {
+                self.confirming_remove_custom = false;
+                self.record_edit();
+                self.remove_custom = true;
+                self.set_status(self.t(Text::CustomPropertiesRemoved).to_string());
+                Command::none()
+            }
+            Message::CancelRemoveCustomProperties => {
+                self.confirming_remove_custom = false;
+                Command::none()
+            }
+            Message::RemoveThumbnail => {
+                self.confirming_remove_thumbnail = true;
+                Command::none()
+            }
+            Message::ConfirmRemoveThumbnail => {
+                self.confirming_remove_thumbnail = false;
+                self.record_edit();
+                self.remove_thumbnail = true;
+                self.set_status(self.t(Text::ThumbnailRemoved).to_string());
+                Command::none()
+            }
+            Message::CancelRemoveThumbnail => {
+                self.confirming_remove_thumbnail = false;
+                Command::none()
+            }
+            Message::CloseRequested if self.dirty => {
+                self.confirming_close = true;
+                Command::none()
+            }
+            Message::CloseRequested | Message::ConfirmClose => window::close(window::Id::MAIN),
+            Message::SaveAndClose => {
+                self.confirming_close = false;
+                if self.is_loading {
+                    // What is running isn't the save this asked for
+                    return Command::none();
+                }
+                let command = self.update(Message::SaveChanges);
+                // Wait for the save only if one (or its confirmation) actually
+                // started: a dry run, the signature warning or a missing file
+                // leave nothing whose result would reset the flag
+                self.close_after_save = self.is_loading && !self.dry_run;
+                return command;
+            }
+            Message::CancelClose => {
+                self.confirming_close = false;
+                Command::none()
+            }
+            Message::RevealInFolder => {
+                if let Some(path) = &self.file_path {
+                    if !path.exists() {
+                        let message = fill(
+                            self.t(Text::FileNoLongerExists),
+                            &[&path.display().to_string()],
+                        );
+                        self.set_error(message);
+                    } else if let Err(e) = reveal_in_file_manager(path) {
+                        self.set_error(fill(self.t(Text::Error), &[&e.to_string()]));
+                    }
+                }
+                Command::none()
+            }
+            // Bumps the modified date to now and saves, in one step
+            Message::TouchAndSave => {
+                if self.is_loading || self.file_path.is_none() {
+                    return Command::none();
+                }
+                self.record_edit();
+                self.metadata.modified = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+                self.update(Message::SaveChanges)
+            }
+            // A save or batch is already running (e.g. Ctrl+S pressed twice)
+            Message::SaveChanges | Message::SaveAs if self.is_loading => Command::none(),
+            Message::SaveChanges | Message::SaveAs if self.dry_run => match &self.file_path {
+                // Reported before the signature check: a dry run breaks nothing
+                Some(path) => {
                     self.is_loading = true;
-                    self.status_message = "正在保存更改...".to_string();
-                    let created = self.created_date.clone();
-                    let modified = self.modified_date.clone();
-                    let last_printed = self.last_printed_date.clone();
                     Command::perform(
-                        save_metadata(path, created, modified, last_printed),
-                        Message::FileSaved,
+                        dry_run_async(
+                            path.clone(),
+                            self.metadata.clone(),
+                            self.save_options(),
+                            self.preferences.language,
+                        ),
+                        Message::DryRunFinished,
                     )
+                }
+                None => {
+                    self.set_status(self.t(Text::NoFileToSave).to_string());
+                    Command::none()
+                }
+            },
+            Message::SaveChanges | Message::SaveAs if self.unconfirmed_signature => {
+                self.set_status(self.t(Text::SignedDocumentWarning).to_string());
+                Command::none()
+            }
+            Message::SaveChanges
+                if self.preferences.confirm_before_save && self.save_confirmation.is_none() =>
+            {
+                match self.file_path.clone() {
+                    Some(path) => {
+                        self.is_loading = true;
+                        Command::perform(
+                            dry_run_async(
+                                path,
+                                self.metadata.clone(),
+                                self.save_options(),
+                                self.preferences.language,
+                            ),
+                            Message::SaveSummaryReady,
+                        )
+                    }
+                    None => {
+                        self.set_status(self.t(Text::NoFileToSave).to_string());
+                        Command::none()
+                    }
+                }
+            }
+            Message::SaveChanges => {
+                self.save_confirmation = None;
+                if let Some(path) = self.file_path.clone() {
+                    self.is_loading = true;
+                    self.set_status(self.t(Text::Saving).to_string());
+                    self.log_date_order_issues();
+                    Command::run(self.start_save(path.clone(), path), |event| match event {
+                        SaveEvent::Progress(progress) => Message::SaveProgressed(progress),
+                        SaveEvent::Finished(result) => Message::FileSaved(result),
+                    })
                 } else {
-                    self.status_message = "未选择要保存的文件.".to_string();
+                    self.set_status(self.t(Text::NoFileToSave).to_string());
+                    Command::none()
+                }
+            }
+            Message::SaveAs => match &self.file_path {
+                Some(path) => Command::perform(
+                    select_save_path_async(path.clone()),
+                    Message::SaveAsPathSelected,
+                ),
+                None => {
+                    self.set_status(self.t(Text::NoFileToSave).to_string());
                     Command::none()
                 }
+            },
+            Message::SaveAsPathSelected(Some(output)) => {
+                let Some(input) = self.file_path.clone() else {
+                    return Command::none();
+                };
+                self.is_loading = true;
+                self.set_status(self.t(Text::Saving).to_string());
+                self.log_date_order_issues();
+                Command::run(
+                    self.start_save(input, output.clone()),
+                    move |event| match event {
+                        SaveEvent::Progress(progress) => Message::SaveProgressed(progress),
+                        SaveEvent::Finished(Ok(report)) => {
+                            Message::FileSavedAs(output.clone(), report)
+                        }
+                        SaveEvent::Finished(Err(e)) => Message::FileSaved(Err(e)),
+                    },
+                )
+            }
+            Message::SaveAsPathSelected(None) => {
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::FileSavedAs(output, report) => {
+                // Carry on editing the copy; the original keeps its old metadata
+                self.is_loading = false;
+                self.dirty = false;
+                self.save_progress = None;
+                self.anonymize = false;
+                self.strip_history = false;
+                self.remove_custom = false;
+                self.remove_thumbnail = false;
+                self.cleared_fields.clear();
+                self.file_path = Some(output.clone());
+                self.summary = document_summary(&output).ok();
+                self.set_status(fill(
+                    self.t(Text::FileSavedAs),
+                    &[&output.display().to_string()],
+                ));
+                self.warn_save_report(&output, report);
+                self.reload_saved(&output);
+                config::remember_recent_file(&mut self.recent_files, output);
+                let _ = config::save_recent_files(&self.recent_files);
+                Command::none()
+            }
+            Message::SaveProgressed(progress) => {
+                self.save_progress = Some(progress);
+                Command::none()
+            }
+            // Transient notes: the log keeps only the "Loading..." line
+            Message::LoadProgressed(LoadEvent::Scanning) => {
+                self.status_message = self.t(Text::ScanningArchive).to_string();
+                Command::none()
+            }
+            Message::LoadProgressed(LoadEvent::ReadingProperties) => {
+                self.status_message = self.t(Text::ReadingProperties).to_string();
+                Command::none()
+            }
+            // start_load delivers the result as FileLoaded
+            Message::LoadProgressed(LoadEvent::Finished(_)) => Command::none(),
+            Message::SpinnerTick => {
+                self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+                Command::none()
+            }
+            Message::CancelSave => {
+                // The worker notices at its next entry; queued batch files are dropped
+                self.cancel_save.store(true, Ordering::Relaxed);
+                self.batch_queue.clear();
+                Command::none()
             }
-            Message::FileSaved(Ok(())) => {
+            Message::FileSaved(Ok(report)) => {
                 self.is_loading = false;
-                self.status_message = "文件保存成功!".to_string();
+                self.dirty = false;
+                self.save_progress = None;
+                self.anonymize = false;
+                self.strip_history = false;
+                self.remove_custom = false;
+                self.remove_thumbnail = false;
+                self.cleared_fields.clear();
+                self.set_status(self.t(Text::FileSaved).to_string());
+                if let Some(path) = self.file_path.clone() {
+                    self.summary = document_summary(&path).ok();
+                    self.warn_save_report(&path, report);
+                    self.reload_saved(&path);
+                }
+                if self.close_after_save {
+                    return window::close(window::Id::MAIN);
+                }
                 Command::none()
             }
             Message::FileSaved(Err(e)) => {
                 self.is_loading = false;
-                self.status_message = format!("保存文件时出错: {}", e);
+                self.close_after_save = false;
+                self.save_progress = None;
+                if let DocxMetaError::Cancelled = *e {
+                    self.set_status(self.t(Text::SaveCancelled).to_string());
+                } else {
+                    let e = error_message(self.preferences.language, &e);
+                    self.set_error(fill(self.t(Text::SaveError), &[&e]));
+                }
                 Command::none()
             }
-        }
-    }
-
-    fn view(&self) -> Element<'_, Message> {
-        let file_display = self
-            .file_path
-            .as_ref()
-            .map_or("未选择文件", |p| p.to_str().unwrap_or("无效路径"));
-
-        let select_button = button("选择 .docx 文件").on_press(Message::SelectFile);
-
-        let mut save_button = button("保存更改");
-        if self.file_path.is_some() {
-            save_button = save_button.on_press(Message::SaveChanges);
-        }
-
-        let content = column(vec![
-            select_button.into(),
-            text(file_display).size(16).into(),
-            row(vec![
-                text("创建日期:").width(Length::Fixed(120.0)).into(),
-                text_input("例如, 2023-01-01T12:00:00Z", &self.created_date)
-                    .on_input(Message::CreatedDateChanged)
-                    .into(),
-            ])
-            .spacing(10)
-            .into(),
-            row(vec![
-                text("修改日期:").width(Length::Fixed(120.0)).into(),
-                text_input("例如, 2023-01-01T13:00:00Z", &self.modified_date)
-                    .on_input(Message::ModifiedDateChanged)
-                    .into(),
-            ])
-            .spacing(10)
-            .into(),
-            row(vec![
-                text("最后打印:").width(Length::Fixed(120.0)).into(),
-                text_input("例如, 2023-01-01T14:00:00Z", &self.last_printed_date)
-                    .on_input(Message::LastPrintedDateChanged)
-                    .into(),
-            ])
-            .spacing(10)
-            .into(),
-            save_button.into(),
-            text(&self.status_message).size(16).into(),
-        ])
-        .spacing(20)
-        .padding(20);
-
-        container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x()
-            .center_y()
-            .into()
-    }
-}
-
-// --- Async Helper Functions ---
-
-async fn select_file_async() -> Option<PathBuf> {
-    FileDialog::new()
-        .add_filter("Word 文档", &["docx"])
-        .pick_file()
-}
-
-async fn load_metadata(path: PathBuf) -> Result<(String, String, String), String> {
-    let file = File::open(&path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-
-    let (created, modified, last_printed) = {
-        let mut core_props_entry = archive
-            .by_name("docProps/core.xml")
-            .map_err(|_| "在压缩包中找不到 docProps/core.xml。".to_string())?;
-        let mut core_props_buffer = Vec::new();
-        core_props_entry
-            .read_to_end(&mut core_props_buffer)
-            .map_err(|e| e.to_string())?;
-        let mut reader = Reader::from_reader(&core_props_buffer[..]);
-        let mut created = String::new();
-        let mut modified = String::new();
-        let mut last_printed = String::new();
-        let mut buf = Vec::new();
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => match e.name().as_ref() {
-                    b"dcterms:created" => {
-                        created = reader.read_text(e.name()).unwrap_or_default().to_string();
-                    }
-                    b"dcterms:modified" => {
-                        modified = reader.read_text(e.name()).unwrap_or_default().to_string();
-                    }
-                    b"cp:lastPrinted" => {
-                        last_printed = reader.read_text(e.name()).unwrap_or_default().to_string();
+            Message::SelectFolder => {
+                self.is_loading = true;
+                self.set_status(self.t(Text::OpeningFolderDialog).to_string());
+                Command::perform(select_folder_async(), Message::FolderSelected)
+            }
+            Message::FolderSelected(Some(dir)) => {
+                self.is_loading = false;
+                let files = match list_documents(&dir) {
+                    Ok(files) => files,
+                    Err(e) => {
+                        self.set_error(fill(self.t(Text::Error), &[&e]));
+                        return Command::none();
                     }
-                    _ => (),
-                },
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(format!("core.xml XML 解析错误: {}", e)),
-                _ => (),
+                };
+                if files.is_empty() {
+                    self.set_status(fill(
+                        self.t(Text::NoDocumentsInFolder),
+                        &[&dir.display().to_string()],
+                    ));
+                    return Command::none();
+                }
+                // Nothing is saved until the list has been reviewed
+                self.batch_candidates = files.into_iter().map(|path| (path, true)).collect();
+                self.set_status(fill(
+                    self.t(Text::BatchReview),
+                    &[&dir.display().to_string()],
+                ));
+                Command::none()
             }
-            buf.clear();
-        }
-        (created, modified, last_printed)
-    };
-
-    Ok((created, modified, last_printed))
-}
-
-async fn save_metadata(
-    path: PathBuf,
-    created_date: String,
-    modified_date: String,
-    last_printed_date: String,
-) -> Result<(), String> {
-    // Validate date formats before proceeding
-    DateTime::parse_from_rfc3339(&created_date.replace("Z", "+00:00")).map_err(|_| {
-        "创建日期' 格式无效。请使用 ISO 8601 (例如：YYYY-MM-DDTHH:MM:SSZ)。".to_string()
-    })?;
-    DateTime::parse_from_rfc3339(&modified_date.replace("Z", "+00:00")).map_err(|_| {
-        "修改日期' 格式无效。请使用 ISO 8601 (例如：YYYY-MM-DDTHH:MM:SSZ)。".to_string()
-    })?;
-    if !last_printed_date.is_empty() {
-        DateTime::parse_from_rfc3339(&last_printed_date.replace("Z", "+00:00")).map_err(|_| {
-            "最后打印日期' 格式无效。请使用 ISO 8601 (例如：YYYY-MM-DDTHH:MM:SSZ)。".to_string()
-        })?;
-    }
-
-    let temp_path = path.with_extension("tmp");
-
-    {
-        let file = File::open(&path).map_err(|e| e.to_string())?;
-        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-        let temp_file = File::create(&temp_path).map_err(|e| e.to_string())?;
-        let mut zip_writer = ZipWriter::new(temp_file);
-        let options: zip::write::FileOptions<'_, ()> =
-            FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i).unwrap();
-            let file_name = file.name();
-            if file_name == "docProps/core.xml" {
-                continue; // Skip old property files
-            }
-            zip_writer
-                .start_file(file.name(), options)
-                .map_err(|e| e.to_string())?;
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-            zip_writer.write_all(&buffer).map_err(|e| e.to_string())?;
-        }
-
-        // Create and write the modified core.xml
-        let new_core_xml =
-            generate_core_xml(&path, &created_date, &modified_date, &last_printed_date)?;
-        zip_writer
-            .start_file("docProps/core.xml", options)
-            .map_err(|e| e.to_string())?;
-        zip_writer
-            .write_all(new_core_xml.as_bytes())
-            .map_err(|e| e.to_string())?;
-
-        zip_writer.finish().map_err(|e| e.to_string())?;
-    }
-
-    fs::rename(&temp_path, &path).map_err(|e| format!("替换原始文件失败: {}", e))
-}
-
-fn generate_core_xml(
-    original_path: &Path,
-    new_created: &str,
-    new_modified: &str,
-    last_printed: &str,
-) -> Result<String, String> {
-    let file = File::open(original_path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-    let mut core_props_entry = archive
-        .by_name("docProps/core.xml")
-        .map_err(|_| "找不到 docProps/core.xml。".to_string())?;
-
-    let mut core_props_buffer = Vec::new();
-    core_props_entry
-        .read_to_end(&mut core_props_buffer)
-        .map_err(|e| e.to_string())?;
-    let mut reader = Reader::from_reader(&core_props_buffer[..]);
-    let mut writer = Writer::new(Cursor::new(Vec::new()));
-    let mut buf = Vec::new();
-    let mut in_target_elem = false;
-
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => {
-                let elem_name = e.name();
-                if elem_name.as_ref() == b"dcterms:created"
-                    || elem_name.as_ref() == b"dcterms:modified"
-                    || elem_name.as_ref() == b"cp:lastPrinted"
-                {
-                    in_target_elem = true;
-                    writer.write_event(Event::Start(e.to_owned())).unwrap();
-
-                    let text_to_write = if elem_name.as_ref() == b"cp:lastPrinted" {
-                        last_printed
-                    } else if elem_name.as_ref() == b"dcterms:created" {
-                        new_created
-                    } else {
-                        new_modified
-                    };
-                    writer
-                        .write_event(Event::Text(BytesText::new(text_to_write)))
-                        .unwrap();
-                } else {
-                    writer.write_event(Event::Start(e.to_owned())).unwrap();
+            Message::BatchCandidateToggled(index, checked) => {
+                if let Some((_, included)) = self.batch_candidates.get_mut(index) {
+                    *included = checked;
+                }
+                Command::none()
+            }
+            Message::SelectAllBatchCandidates(checked) => {
+                for (_, included) in &mut self.batch_candidates {
+                    *included = checked;
                 }
+                Command::none()
             }
-            Ok(Event::Text(_)) if in_target_elem => {
-                // Skip the original text content of the target element
+            Message::MoveBatchCandidate(from, to) => {
+                if from < self.batch_candidates.len() && to < self.batch_candidates.len() {
+                    let candidate = self.batch_candidates.remove(from);
+                    self.batch_candidates.insert(to, candidate);
+                }
+                Command::none()
             }
-            Ok(Event::End(e)) => {
-                if in_target_elem {
-                    let elem_name = e.name();
-                    if elem_name.as_ref() == b"dcterms:created"
-                        || elem_name.as_ref() == b"dcterms:modified"
-                        || elem_name.as_ref() == b"cp:lastPrinted"
-                    {
-                        in_target_elem = false;
-                    }
+            Message::CancelBatchSelection => {
+                self.batch_candidates.clear();
+                self.set_status(self.t(Text::FolderSelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::StartBatch => {
+                let files: Vec<PathBuf> = self
+                    .batch_candidates
+                    .drain(..)
+                    .filter_map(|(path, included)| included.then_some(path))
+                    .collect();
+                if files.is_empty() {
+                    return Command::none();
                 }
-                writer.write_event(Event::End(e.to_owned())).unwrap();
+                self.is_loading = true;
+                self.batch_total = files.len();
+                self.batch_files = files.clone();
+                // Reversed so that popping yields the files in the chosen order
+                self.batch_queue = files.into_iter().rev().collect();
+                self.batch_failures = 0;
+                self.batch_report.clear();
+                self.log_date_order_issues();
+                self.status_message = fill(
+                    self.t(Text::BatchProgress),
+                    &["0", &self.batch_total.to_string()],
+                );
+                self.save_next_batch_file()
+            }
+            Message::FolderSelected(None) => {
+                self.is_loading = false;
+                self.set_status(self.t(Text::FolderSelectionCancelled).to_string());
+                Command::none()
             }
-            Ok(Event::Eof) => break,
-            Ok(e) => {
-                writer.write_event(e).unwrap();
+            Message::BatchFileSaved(path, result) => {
+                self.save_progress = None;
+                let name = path.display().to_string();
+                let line = match result {
+                    Ok(report) => {
+                        let line = fill(self.t(Text::BatchFileSucceeded), &[&name]);
+                        self.log_entry(line.clone(), false);
+                        self.warn_save_report(&path, report);
+                        line
+                    }
+                    Err(e) => {
+                        self.batch_failures += 1;
+                        let e = error_message(self.preferences.language, &e);
+                        let line = fill(self.t(Text::BatchFileFailed), &[&name, &e]);
+                        self.log_entry(line.clone(), true);
+                        line
+                    }
+                };
+                self.batch_report.push(line);
+                let done = self.batch_total - self.batch_queue.len();
+                self.status_message = fill(
+                    self.t(Text::BatchProgress),
+                    &[&done.to_string(), &self.batch_total.to_string()],
+                );
+                self.save_next_batch_file()
             }
-            Err(e) => return Err(format!("XML (core) 处理错误: {}", e)),
-        }
-        buf.clear();
-    }
-
-    String::from_utf8(writer.into_inner().into_inner()).map_err(|e| e.to_string())
+            Message::ExportJson => {
+                if let Some(path) = self.file_path.clone() {
+                    let sidecar = self.metadata_sidecar(&path);
+                    Command::perform(export_json(path, sidecar), Message::JsonExported)
+                } else {
+                    self.set_status(self.t(Text::NoFileSelected).to_string());
+                    Command::none()
+                }
+            }
+            Message::JsonExported(Ok(json_path)) => {
+                self.set_status(fill(
+                    self.t(Text::JsonExported),
+                    &[&json_path.display().to_string()],
+                ));
+                Command::none()
+            }
+            Message::JsonExported(Err(e)) => {
+                self.set_error(fill(self.t(Text::Error), &[&e]));
+                Command::none()
+            }
+            Message::ExportCsv => {
+                let filter_name = self.t(Text::CsvFilter);
+                Command::perform(select_csv_path_async(filter_name), Message::CsvPathSelected)
+            }
+            Message::CsvPathSelected(Some(csv_path)) => {
+                let files = self.csv_files();
+                Command::perform(
+                    export_csv(files, csv_path, self.preferences.language),
+                    Message::CsvExported,
+                )
+            }
+            Message::CsvPathSelected(None) => {
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::CsvExported(Ok((csv_path, skipped))) => {
+                for line in skipped {
+                    self.log_entry(fill(self.t(Text::CsvRowSkipped), &[&line]), true);
+                }
+                self.set_status(fill(
+                    self.t(Text::CsvExported),
+                    &[&csv_path.display().to_string()],
+                ));
+                Command::none()
+            }
+            Message::CsvExported(Err(e)) => {
+                self.set_error(fill(self.t(Text::Error), &[&e]));
+                Command::none()
+            }
+            Message::ImportJson => {
+                let filter_name = self.t(Text::JsonFilter);
+                Command::perform(select_json_async(filter_name), Message::JsonFileSelected)
+            }
+            Message::JsonFileSelected(Some(json_path)) => {
+                Command::perform(import_json(json_path), Message::JsonImported)
+            }
+            Message::JsonFileSelected(None) => {
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::JsonImported(Ok(sidecar)) => {
+                // With nothing to overwrite there is nothing to preview
+                if self.metadata == CoreMetadata::default() {
+                    self.apply_import(&sidecar, false);
+                } else {
+                    self.pending_import = Some(sidecar);
+                }
+                Command::none()
+            }
+            Message::MergeImportToggled(merge) => {
+                self.merge_import = merge;
+                Command::none()
+            }
+            Message::ApplyImport => {
+                if let Some(sidecar) = self.pending_import.take() {
+                    self.apply_import(&sidecar, self.merge_import);
+                }
+                Command::none()
+            }
+            Message::CancelImport => {
+                self.pending_import = None;
+                Command::none()
+            }
+            Message::JsonImported(Err(e)) => {
+                let message = self.import_error_message(&e);
+                self.set_error(fill(self.t(Text::Error), &[&message]));
+                Command::none()
+            }
+            Message::Anonymize => {
+                let cleared = [
+                    "dc:title",
+                    "dc:creator",
+                    "cp:lastModifiedBy",
+                    "Company",
+                    "Manager",
+                ];
+                self.record_edit();
+                self.metadata.company.clear();
+                self.metadata.manager.clear();
+                self.anonymize = true;
+                let mut status = fill(self.t(Text::AnonymizeCleared), &[&cleared.join(", ")]);
+                if self.reset_dates_on_anonymize {
+                    match normalize_date(&self.neutral_timestamp) {
+                        Some(timestamp) => {
+                            self.metadata.created = timestamp.clone();
+                            self.metadata.modified = timestamp.clone();
+                            status.push(' ');
+                            status
+                                .push_str(&fill(self.t(Text::AnonymizeDatesReset), &[&timestamp]));
+                        }
+                        None => {
+                            status.push(' ');
+                            status.push_str(&fill(
+                                self.t(Text::InvalidNeutralTimestamp),
+                                &[&self.neutral_timestamp],
+                            ));
+                        }
+                    }
+                }
+                self.set_status(status);
+                Command::none()
+            }
+            Message::StripEditingHistory => {
+                // Reported with the values being cleared, read from the file
+                // for lastModifiedBy as it has no input
+                let last_modified_by = self
+                    .file_path
+                    .as_ref()
+                    .and_then(|path| load_authors(path).ok())
+                    .map(|authors| authors.last_modified_by)
+                    .unwrap_or_default();
+                let total_time = self.metadata.total_time.clone();
+                self.record_edit();
+                self.metadata.total_time = "0".to_string();
+                self.strip_history = true;
+                self.set_status(fill(
+                    self.t(Text::EditingHistoryStripped),
+                    &[&total_time, &last_modified_by],
+                ));
+                Command::none()
+            }
+            Message::ResetDatesOnAnonymizeToggled(enabled) => {
+                self.reset_dates_on_anonymize = enabled;
+                Command::none()
+            }
+            Message::SyncFileTimesToggled(enabled) => {
+                self.preferences.sync_file_times = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::SyncCreatedTimeToggled(enabled) => {
+                self.preferences.sync_created_time = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::VerifyAfterSaveToggled(enabled) => {
+                self.preferences.verify_after_save = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ConfirmBeforeSaveToggled(enabled) => {
+                self.preferences.confirm_before_save = enabled;
+                self.save_preferences();
+                self.save_confirmation = None;
+                Command::none()
+            }
+            Message::SaveSummaryReady(Ok(changes)) => {
+                self.is_loading = false;
+                self.save_confirmation = Some(changes);
+                self.set_status(self.t(Text::ConfirmSaveHint).to_string());
+                Command::none()
+            }
+            Message::SaveSummaryReady(Err(e)) => {
+                self.is_loading = false;
+                self.close_after_save = false;
+                self.set_error(fill(self.t(Text::SaveError), &[&e]));
+                Command::none()
+            }
+            Message::CancelSaveConfirmation => {
+                self.save_confirmation = None;
+                self.close_after_save = false;
+                Command::none()
+            }
+            Message::DryRunToggled(enabled) => {
+                self.dry_run = enabled;
+                Command::none()
+            }
+            Message::BlockDateOrderToggled(enabled) => {
+                self.preferences.block_date_order = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::CheckFutureDatesToggled(enabled) => {
+                self.preferences.check_future_dates = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::BlockFutureDatesToggled(enabled) => {
+                self.preferences.block_future_dates = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::FutureDateToleranceChanged(minutes) => {
+                self.preferences.future_date_tolerance = minutes;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::IndentXmlToggled(enabled) => {
+                self.preferences.indent_xml = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::LfLineEndingsToggled(enabled) => {
+                self.preferences.lf_line_endings = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::StampPartsModifiedToggled(enabled) => {
+                self.preferences.stamp_parts_modified = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ReproducibleZipToggled(enabled) => {
+                self.preferences.reproducible_zip = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::CompressionSelected(choice) => {
+                self.preferences.compression = choice.level;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::DateProfileSelected(choice) => {
+                self.preferences.date_profile = choice.profile;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::DryRunFinished(Ok(changes)) => {
+                self.is_loading = false;
+                for change in &changes {
+                    self.log_entry(self.change_line(change), false);
+                }
+                if self.creates_app_xml() {
+                    self.log_entry(self.t(Text::CreatingAppXml).to_string(), false);
+                }
+                self.set_status(fill(
+                    self.t(Text::DryRunFinished),
+                    &[&changes.len().to_string()],
+                ));
+                Command::none()
+            }
+            Message::DryRunFinished(Err(e)) => {
+                self.is_loading = false;
+                self.set_error(fill(self.t(Text::SaveError), &[&e]));
+                Command::none()
+            }
+            Message::Validate => match &self.file_path {
+                Some(path) if !self.is_loading => {
+                    self.is_loading = true;
+                    Command::perform(
+                        validate_async(
+                            path.clone(),
+                            self.metadata.clone(),
+                            self.save_options(),
+                            self.preferences.language,
+                        ),
+                        Message::Validated,
+                    )
+                }
+                _ => Command::none(),
+            },
+            Message::Validated(problems) => {
+                self.is_loading = false;
+                if problems.is_empty() {
+                    self.set_status(self.t(Text::ValidationPassed).to_string());
+                } else {
+                    for problem in &problems {
+                        self.log_entry(problem.clone(), true);
+                    }
+                    self.set_error(fill(
+                        self.t(Text::ValidationFailed),
+                        &[&problems.len().to_string()],
+                    ));
+                }
+                self.validation = Some(problems);
+                Command::none()
+            }
+            Message::NeutralTimestampChanged(timestamp) => {
+                self.neutral_timestamp = timestamp;
+                Command::none()
+            }
+            Message::DateShiftChanged(shift) => {
+                self.date_shift = shift;
+                Command::none()
+            }
+            Message::ShiftDates => {
+                let Some(offset) = parse_offset(&self.date_shift) else {
+                    self.set_error(fill(self.t(Text::InvalidDateShift), &[&self.date_shift]));
+                    return Command::none();
+                };
+                // Empty or unparseable dates are skipped, not treated as errors
+                let shifted: Vec<(MetadataField, String)> = [
+                    MetadataField::Created,
+                    MetadataField::Modified,
+                    MetadataField::LastPrinted,
+                ]
+                .into_iter()
+                .filter_map(|field| Some((field, shift_date(self.metadata.get(field), offset)?)))
+                .collect();
+                if !shifted.is_empty() {
+                    self.record_edit();
+                }
+                let count = shifted.len();
+                for (field, value) in shifted {
+                    *self.metadata.get_mut(field) = value;
+                }
+                self.set_status(fill(self.t(Text::DatesShifted), &[&count.to_string()]));
+                Command::none()
+            }
+            Message::LanguageSelected(language) => {
+                self.preferences.language = language;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ToggleDatePicker(field) => {
+                self.date_picker = match self.date_picker {
+                    Some((open, _)) if open == field => None,
+                    _ => {
+                        // Start on the month of the current value, if it parses
+                        let date = parse_date_with_offset(self.date_value(field))
+                            .map_or_else(|| Utc::now().date_naive(), |dt| dt.date_naive());
+                        Some((field, Calendar::containing(date)))
+                    }
+                };
+                Command::none()
+            }
+            Message::DatePickerPreviousMonth => {
+                if let Some((_, calendar)) = &mut self.date_picker {
+                    *calendar = calendar.previous_month();
+                }
+                Command::none()
+            }
+            Message::DatePickerNextMonth => {
+                if let Some((_, calendar)) = &mut self.date_picker {
+                    *calendar = calendar.next_month();
+                }
+                Command::none()
+            }
+            Message::DatePicked(date) => {
+                if let Some((field, _)) = self.date_picker.take() {
+                    self.record_edit();
+                    let value = if self.preferences.local_time_display {
+                        local_date_with_day(self.date_value(field), date)
+                    } else {
+                        date_with_day(self.date_value(field), date)
+                    };
+                    *self.date_value_mut(field) = value;
+                }
+                Command::none()
+            }
+            Message::ToggleTheme => {
+                self.preferences.theme = self.preferences.theme.toggled();
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ClearLog => {
+                self.log.clear();
+                Command::none()
+            }
+            Message::Undo => {
+                if let Some(previous) = self.undo_stack.pop_back() {
+                    self.redo_stack.push(self.snapshot());
+                    self.restore(previous);
+                }
+                Command::none()
+            }
+            Message::Redo => {
+                if let Some(next) = self.redo_stack.pop() {
+                    self.undo_stack.push_back(self.snapshot());
+                    self.restore(next);
+                }
+                Command::none()
+            }
+            Message::CopyFrom => {
+                let filter_name = self.t(Text::DocumentFilter);
+                Command::perform(select_file_async(filter_name), Message::CopySourceSelected)
+            }
+            Message::CopySourceSelected(Some(path)) => Command::perform(
+                load_metadata_async(path.clone(), self.preferences.language),
+                move |result| Message::CopySourceLoaded(path.clone(), result),
+            ),
+            Message::CopySourceSelected(None) => {
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::CopySourceLoaded(path, Ok(metadata)) => {
+                self.set_status(fill(
+                    self.t(Text::MetadataCopied),
+                    &[&path.display().to_string()],
+                ));
+                self.clipboard = Some((path, metadata));
+                Command::none()
+            }
+            Message::CopySourceLoaded(_, Err(e)) => {
+                self.set_error(fill(self.t(Text::Error), &[&e]));
+                Command::none()
+            }
+            Message::PasteFieldToggled(field, enabled) => {
+                self.paste_fields.retain(|selected| *selected != field);
+                if enabled {
+                    self.paste_fields.push(field);
+                }
+                Command::none()
+            }
+            Message::PasteInto => {
+                if let Some((_, copied)) = &self.clipboard {
+                    let copied = copied.clone();
+                    self.record_edit();
+                    for field in &self.paste_fields {
+                        *self.metadata.get_mut(*field) = copied.get(*field).to_string();
+                    }
+                    self.set_status(self.t(Text::MetadataPasted).to_string());
+                }
+                Command::none()
+            }
+            Message::HighContrastToggled(enabled) => {
+                self.preferences.high_contrast = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::LocalTimeDisplayToggled(enabled) => {
+                self.preferences.local_time_display = enabled;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::UiScaleChanged(scale) => {
+                self.preferences.ui_scale = scale;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ReadOnlyToggled(enabled) => {
+                self.preferences.read_only = enabled;
+                self.date_picker = None;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                Command::none()
+            }
+            Message::ToggleXmlPreview => {
+                self.show_xml_preview = !self.show_xml_preview;
+                Command::none()
+            }
+            Message::ShowXmlDiffToggled(show) => {
+                self.show_xml_diff = show;
+                Command::none()
+            }
+            Message::ToggleComparison => {
+                self.show_comparison = !self.show_comparison;
+                Command::none()
+            }
+            Message::CompareSelect(slot) => {
+                let filter_name = self.t(Text::DocumentFilter);
+                Command::perform(select_file_async(filter_name), move |path| {
+                    Message::CompareFileSelected(slot, path)
+                })
+            }
+            Message::CompareFileSelected(slot, Some(path)) => Command::perform(
+                load_metadata_async(path.clone(), self.preferences.language),
+                move |result| Message::CompareFileLoaded(slot, path.clone(), result),
+            ),
+            Message::CompareFileSelected(_, None) => {
+                self.set_status(self.t(Text::SelectionCancelled).to_string());
+                Command::none()
+            }
+            Message::CompareFileLoaded(slot, path, Ok(metadata)) => {
+                self.compared[slot] = Some((path, metadata));
+                Command::none()
+            }
+            Message::CompareFileLoaded(_, _, Err(e)) => {
+                self.set_error(fill(self.t(Text::Error), &[&e]));
+                Command::none()
+            }
+        };
+        // Any message may have loaded, saved or edited something
+        self.sync_description_editor();
+        self.refresh_xml_preview();
+        command
+    }
+
+    fn theme(&self) -> Theme {
+        if self.preferences.high_contrast {
+            config::high_contrast_theme()
+        } else {
+            self.preferences.theme.theme()
+        }
+    }
+
+    // Scales text along with the spacing around it, so layouts keep their shape
+    fn scale_factor(&self) -> f64 {
+        let range = config::UI_SCALE_RANGE;
+        f64::from(
+            self.preferences
+                .ui_scale
+                .clamp(*range.start(), *range.end()),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let events = event::listen_with(|event, _status| match event {
+            iced::Event::Window(_, window::Event::CloseRequested) => Some(Message::CloseRequested),
+            iced::Event::Window(_, window::Event::FileDropped(path)) => {
+                if is_supported_document(&path) {
+                    Some(Message::FileSelected(Some(path)))
+                } else {
+                    Some(Message::UnsupportedFileDropped(path))
+                }
+            }
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Character(c),
+                modifiers,
+                ..
+            }) if modifiers.command() && c.eq_ignore_ascii_case("z") => {
+                if modifiers.shift() {
+                    Some(Message::Redo)
+                } else {
+                    Some(Message::Undo)
+                }
+            }
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Character(c),
+                modifiers,
+                ..
+            }) if modifiers.command() && c.eq_ignore_ascii_case("s") => Some(Message::SaveChanges),
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Character(c),
+                modifiers,
+                ..
+            }) if modifiers.command() && c.eq_ignore_ascii_case("o") => Some(Message::SelectFile),
+            _ => None,
+        });
+        if !self.is_loading {
+            return events;
+        }
+        // Dropped once loading ends, which stops the ticking thread
+        let spinner = iced::subscription::unfold(
+            "spinner",
+            None,
+            |ticks: Option<mpsc::UnboundedReceiver<()>>| async move {
+                let mut ticks = ticks.unwrap_or_else(spinner_ticks);
+                ticks.next().await;
+                (Message::SpinnerTick, Some(ticks))
+            },
+        );
+        Subscription::batch([events, spinner])
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let mut file_display = self
+            .file_path
+            .as_ref()
+            .map_or(self.t(Text::NoFileSelected), |p| {
+                p.to_str().unwrap_or(self.t(Text::InvalidPath))
+            })
+            .to_string();
+        if let Some(summary) = &self.summary {
+            file_display = format!("{} ({})", file_display, self.t(kind_label(summary.kind)));
+        }
+
+        let select_button = button(self.t(Text::SelectFile)).on_press(Message::SelectFile);
+        let editable = !self.preferences.read_only;
+
+        let mut folder_button = button(self.t(Text::SelectFolder));
+        if !self.is_loading && editable {
+            folder_button = folder_button.on_press(Message::SelectFolder);
+        }
+
+        let mut save_button = button(self.t(match self.save_confirmation {
+            Some(_) => Text::ConfirmSave,
+            None => Text::SaveChanges,
+        }));
+        let mut save_as_button = button(self.t(Text::SaveAs));
+        let mut touch_button = button(self.t(Text::TouchAndSave));
+        let mut revert_button = button(self.t(Text::Revert));
+        if self.file_path.is_some() && !self.is_loading {
+            if editable {
+                touch_button = touch_button.on_press(Message::TouchAndSave);
+            }
+            revert_button = revert_button.on_press(Message::Revert);
+        }
+        let mut export_button = button(self.t(Text::ExportJson));
+        let reveal_button = button(self.t(Text::RevealInFolder))
+            .on_press_maybe(self.file_path.as_ref().map(|_| Message::RevealInFolder));
+        let import_button = button(self.t(Text::ImportJson))
+            .on_press_maybe(editable.then_some(Message::ImportJson));
+        let validate_button = button(self.t(Text::Validate)).on_press_maybe(
+            (self.file_path.is_some() && !self.is_loading).then_some(Message::Validate),
+        );
+        let csv_button = button(self.t(Text::ExportCsv))
+            .on_press_maybe((!self.csv_files().is_empty()).then_some(Message::ExportCsv));
+        let mut anonymize_button = button(self.t(Text::Anonymize));
+        let mut strip_history_button = button(self.t(Text::StripEditingHistory));
+        if self.file_path.is_some() && !self.unconfirmed_signature && editable {
+            save_button = save_button.on_press(Message::SaveChanges);
+            save_as_button = save_as_button.on_press(Message::SaveAs);
+        }
+        if self.file_path.is_some() {
+            export_button = export_button.on_press(Message::ExportJson);
+        }
+        if self.file_path.is_some() && editable {
+            anonymize_button = anonymize_button.on_press(Message::Anonymize);
+            strip_history_button = strip_history_button.on_press(Message::StripEditingHistory);
+        }
+
+        let content = column(vec![
+            row(vec![
+                select_button.into(),
+                folder_button.into(),
+                checkbox(self.t(Text::ReadOnly), self.preferences.read_only)
+                    .on_toggle(Message::ReadOnlyToggled)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            self.settings_view(),
+            value_text(file_display).size(16).into(),
+            self.summary_view(),
+            self.signature_warning_view(),
+            self.close_prompt_view(),
+            self.save_confirmation_view(),
+            self.revert_prompt_view(),
+            self.remove_custom_prompt_view(),
+            self.remove_thumbnail_prompt_view(),
+            self.import_preview_view(),
+            self.batch_selection_view(),
+            self.date_row(
+                DateField::Created,
+                Text::CreatedLabel,
+                Text::CreatedPlaceholder,
+                Message::CreatedDateChanged,
+            ),
+            self.date_row(
+                DateField::Modified,
+                Text::ModifiedLabel,
+                Text::ModifiedPlaceholder,
+                Message::ModifiedDateChanged,
+            ),
+            self.date_row(
+                DateField::LastPrinted,
+                Text::LastPrintedLabel,
+                Text::LastPrintedPlaceholder,
+                Message::LastPrintedDateChanged,
+            ),
+            self.date_order_view(),
+            row(vec![
+                text(self.t(Text::ShiftBy))
+                    .width(Length::Fixed(120.0))
+                    .into(),
+                text_input(self.t(Text::DateShiftPlaceholder), &self.date_shift)
+                    .on_input(Message::DateShiftChanged)
+                    .on_submit(Message::ShiftDates)
+                    .width(Length::Fixed(220.0))
+                    .into(),
+                button(self.t(Text::ShiftDates))
+                    .on_press_maybe(editable.then_some(Message::ShiftDates))
+                    .into(),
+                self.copy_date_button(
+                    Text::CopyCreatedToModified,
+                    DateField::Created,
+                    DateField::Modified,
+                ),
+                self.copy_date_button(
+                    Text::CopyModifiedToCreated,
+                    DateField::Modified,
+                    DateField::Created,
+                ),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            self.number_row(
+                MetadataField::Revision,
+                Text::RevisionPlaceholder,
+                Message::RevisionChanged,
+            ),
+            self.text_row(
+                MetadataField::ContentStatus,
+                Text::ContentStatusPlaceholder,
+                Message::ContentStatusChanged,
+            ),
+            match editable {
+                true => row(vec![
+                    text(self.t(Text::DescriptionLabel))
+                        .width(Length::Fixed(120.0))
+                        .into(),
+                    text_editor(&self.description_editor)
+                        .on_action(Message::DescriptionEdited)
+                        .height(Length::Fixed(100.0))
+                        .into(),
+                    clear_button(MetadataField::Description),
+                ])
+                .spacing(10)
+                .into(),
+                false => self.read_only_row(Text::DescriptionLabel, &self.metadata.description),
+            },
+            self.keywords_row(),
+            match editable {
+                true => self.language_row(),
+                false => self.read_only_row(Text::DocumentLanguageLabel, &self.metadata.language),
+            },
+            row(vec![
+                self.text_row(
+                    MetadataField::Category,
+                    Text::CategoryPlaceholder,
+                    Message::CategoryChanged,
+                ),
+                self.text_row(
+                    MetadataField::Version,
+                    Text::VersionPlaceholder,
+                    Message::VersionChanged,
+                ),
+            ])
+            .spacing(20)
+            .into(),
+            self.text_row(
+                MetadataField::Identifier,
+                Text::IdentifierPlaceholder,
+                Message::IdentifierChanged,
+            ),
+            self.text_row(
+                MetadataField::Template,
+                Text::TemplatePlaceholder,
+                Message::TemplateChanged,
+            ),
+            row(vec![
+                self.text_row(
+                    MetadataField::Application,
+                    Text::ApplicationPlaceholder,
+                    Message::ApplicationChanged,
+                ),
+                self.text_row(
+                    MetadataField::AppVersion,
+                    Text::AppVersionPlaceholder,
+                    Message::AppVersionChanged,
+                ),
+            ])
+            .spacing(20)
+            .into(),
+            self.text_row(
+                MetadataField::Company,
+                Text::CompanyPlaceholder,
+                Message::CompanyChanged,
+            ),
+            self.text_row(
+                MetadataField::Manager,
+                Text::ManagerPlaceholder,
+                Message::ManagerChanged,
+            ),
+            row(vec![
+                self.number_row(
+                    MetadataField::TotalTime,
+                    Text::TotalTimePlaceholder,
+                    Message::TotalTimeChanged,
+                ),
+                self.number_row(
+                    MetadataField::Pages,
+                    Text::CountPlaceholder,
+                    Message::PagesChanged,
+                ),
+            ])
+            .spacing(20)
+            .into(),
+            row(vec![
+                self.number_row(
+                    MetadataField::Words,
+                    Text::CountPlaceholder,
+                    Message::WordsChanged,
+                ),
+                self.number_row(
+                    MetadataField::Characters,
+                    Text::CountPlaceholder,
+                    Message::CharactersChanged,
+                ),
+            ])
+            .spacing(20)
+            .into(),
+            self.number_row(
+                MetadataField::DocSecurity,
+                Text::DocSecurityPlaceholder,
+                Message::DocSecurityChanged,
+            ),
+            self.flags_row(),
+            row(vec![
+                save_button.into(),
+                save_as_button.into(),
+                touch_button.into(),
+                revert_button.into(),
+                validate_button.into(),
+                export_button.into(),
+                import_button.into(),
+                csv_button.into(),
+                reveal_button.into(),
+                checkbox(self.t(Text::DryRun), self.dry_run)
+                    .on_toggle(Message::DryRunToggled)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            row(vec![
+                anonymize_button.into(),
+                strip_history_button.into(),
+                button(self.t(Text::RemoveCustomProperties))
+                    .on_press_maybe(
+                        (self
+                            .summary
+                            .as_ref()
+                            .is_some_and(|s| s.has_custom_properties)
+                            && !self.remove_custom
+                            && editable)
+                            .then_some(Message::RemoveCustomProperties),
+                    )
+                    .into(),
+                button(self.t(Text::RemoveThumbnail))
+                    .on_press_maybe(
+                        (self.summary.as_ref().is_some_and(|s| s.has_thumbnail)
+                            && !self.remove_thumbnail
+                            && editable)
+                            .then_some(Message::RemoveThumbnail),
+                    )
+                    .into(),
+                checkbox(
+                    self.t(Text::ResetDatesOnAnonymize),
+                    self.reset_dates_on_anonymize,
+                )
+                .on_toggle(Message::ResetDatesOnAnonymizeToggled)
+                .into(),
+                text_input("2000-01-01T00:00:00Z", &self.neutral_timestamp)
+                    .on_input(Message::NeutralTimestampChanged)
+                    .width(Length::Fixed(220.0))
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            self.clipboard_view(),
+            self.comparison_view(),
+            self.status_view(),
+            self.validation_view(),
+            self.save_progress_view(),
+            self.recent_files_view(),
+            self.xml_preview_view(),
+            self.log_view(),
+        ])
+        .spacing(20)
+        .padding(20);
+
+        container(scrollable(content))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
+
+impl DocxApp {
+    fn t(&self, key: Text) -> &'static str {
+        tr(self.preferences.language, key)
+    }
+
+    fn import_error_message(&self, error: &ImportError) -> String {
+        match error {
+            ImportError::Read(e) => e.clone(),
+            ImportError::Parse(e) => fill(self.t(Text::JsonParseError), &[e]),
+            ImportError::UnknownFields(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(key, suggestion)| match suggestion {
+                        Some(name) => fill(self.t(Text::JsonFieldSuggestion), &[key, name]),
+                        None => format!("'{}'", key),
+                    })
+                    .collect();
+                fill(self.t(Text::JsonUnknownFields), &[&fields.join(", ")])
+            }
+            ImportError::SchemaVersion(version) => {
+                fill(self.t(Text::JsonSchemaVersion), &[&version.to_string()])
+            }
+            ImportError::InvalidValue { kind, field, value } => {
+                fill(self.t(*kind), &[field, value])
+            }
+        }
+    }
+
+    // The CSV report covers the last batch, or else the open file
+    fn csv_files(&self) -> Vec<PathBuf> {
+        if self.batch_files.is_empty() {
+            self.file_path.iter().cloned().collect()
+        } else {
+            self.batch_files.clone()
+        }
+    }
+
+    // Warnings and failed fields; brighter on the high-contrast background
+    fn error_color(&self) -> iced::Color {
+        if self.preferences.high_contrast {
+            iced::Color::from_rgb(1.0, 0.35, 0.35)
+        } else {
+            iced::Color::from_rgb(0.8, 0.2, 0.2)
+        }
+    }
+
+    fn success_color(&self) -> iced::Color {
+        if self.preferences.high_contrast {
+            iced::Color::from_rgb(0.0, 1.0, 0.4)
+        } else {
+            iced::Color::from_rgb(0.1, 0.6, 0.2)
+        }
+    }
+
+    // Best-effort, like the recent files list
+    fn save_preferences(&self) {
+        let _ = config::save_preferences(&self.preferences);
+    }
+
+    fn compression_choice(&self, level: CompressionLevel) -> CompressionChoice {
+        let label = match level {
+            CompressionLevel::Normal => Text::CompressionNormal,
+            CompressionLevel::Store => Text::CompressionStore,
+            CompressionLevel::Fast => Text::CompressionFast,
+            CompressionLevel::Best => Text::CompressionBest,
+        };
+        CompressionChoice {
+            level,
+            label: self.t(label),
+        }
+    }
+
+    fn date_profile_choice(&self, profile: DateProfile) -> DateProfileChoice {
+        let label = match profile {
+            DateProfile::Word => Text::DateProfileWord,
+            DateProfile::StrictWord => Text::DateProfileStrictWord,
+            DateProfile::AsEntered => Text::DateProfileAsEntered,
+        };
+        DateProfileChoice {
+            profile,
+            label: self.t(label),
+        }
+    }
+
+    fn set_status(&mut self, message: String) {
+        self.log_entry(message.clone(), false);
+        self.status_message = message;
+    }
+
+    fn set_error(&mut self, message: String) {
+        self.log_entry(message.clone(), true);
+        self.status_message = message;
+    }
+
+    // Adds to the history without touching the status line
+    fn log_entry(&mut self, message: String, is_error: bool) {
+        self.log.push(LogEntry {
+            time: Local::now(),
+            message,
+            is_error,
+        });
+    }
+
+    fn date_value(&self, field: DateField) -> &str {
+        match field {
+            DateField::Created => &self.metadata.created,
+            DateField::Modified => &self.metadata.modified,
+            DateField::LastPrinted => &self.metadata.last_printed,
+        }
+    }
+
+    fn date_value_mut(&mut self, field: DateField) -> &mut String {
+        match field {
+            DateField::Created => &mut self.metadata.created,
+            DateField::Modified => &mut self.metadata.modified,
+            DateField::LastPrinted => &mut self.metadata.last_printed,
+        }
+    }
+
+    // The date as its input shows it: converted to the system timezone when
+    // that preference is on. Text that isn't a full date is shown as typed.
+    fn date_display(&self, field: DateField) -> String {
+        let value = self.date_value(field);
+        match self.preferences.local_time_display {
+            true => local_display(value).unwrap_or_else(|| value.to_string()),
+            false => value.to_string(),
+        }
+    }
+
+    // Stores typed text, converting local times back to UTC when they are shown
+    fn set_date(&mut self, field: DateField, typed: String) {
+        *self.date_value_mut(field) = match self.preferences.local_time_display {
+            true => local_input_to_utc(&typed).unwrap_or(typed),
+            false => typed,
+        };
+    }
+
+    // Copies one date into another; nothing to copy from an empty field
+    fn copy_date_button(
+        &self,
+        label: Text,
+        from: DateField,
+        to: DateField,
+    ) -> Element<'_, Message> {
+        let enabled = !self.preferences.read_only && !self.date_value(from).trim().is_empty();
+        button(self.t(label))
+            .on_press_maybe(enabled.then_some(Message::CopyDate(from, to)))
+            .into()
+    }
+
+    // A date field row: the free-text input stays available for exact values,
+    // with the calendar shown underneath while it is toggled open.
+    fn date_row(
+        &self,
+        field: DateField,
+        label: Text,
+        placeholder: Text,
+        on_input: fn(String) -> Message,
+    ) -> Element<'_, Message> {
+        if self.preferences.read_only {
+            return self.read_only_row(label, &self.date_display(field));
+        }
+        let input_row = row(vec![
+            text(self.t(label)).width(Length::Fixed(120.0)).into(),
+            text_input(self.t(placeholder), &self.date_display(field))
+                .on_input(on_input)
+                .into(),
+            button(self.t(Text::PickDate))
+                .on_press(Message::ToggleDatePicker(field))
+                .into(),
+            clear_button(field.metadata_field()),
+        ])
+        .spacing(10);
+
+        match self.date_picker {
+            Some((open, calendar)) if open == field => {
+                let selected =
+                    parse_date_with_offset(&self.date_display(field)).map(|dt| dt.date_naive());
+                column(vec![
+                    input_row.into(),
+                    calendar.view(
+                        selected,
+                        weekdays(self.preferences.language),
+                        Message::DatePicked,
+                        Message::DatePickerPreviousMonth,
+                        Message::DatePickerNextMonth,
+                    ),
+                ])
+                .spacing(10)
+                .into()
+            }
+            _ => input_row.into(),
+        }
+    }
+
+    // Keywords as removable chips, plus an input adding more. The stored
+    // string is only rewritten once the list is edited.
+    fn keywords_row(&self) -> Element<'_, Message> {
+        if self.preferences.read_only {
+            return self.read_only_row(Text::KeywordsLabel, &self.metadata.keywords);
+        }
+        let chips: Vec<Element<'_, Message>> = split_keywords(&self.metadata.keywords)
+            .into_iter()
+            .enumerate()
+            .map(|(index, keyword)| {
+                button(value_text(format!("{} ×", keyword)).size(14))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(Message::RemoveKeyword(index))
+                    .into()
+            })
+            .collect();
+        row(vec![
+            text(self.t(Text::KeywordsLabel))
+                .width(Length::Fixed(120.0))
+                .into(),
+            column(vec![
+                scrollable(row(chips).spacing(6))
+                    .direction(scrollable::Direction::Horizontal(
+                        scrollable::Properties::default(),
+                    ))
+                    .into(),
+                row(vec![
+                    text_input(self.t(Text::KeywordsPlaceholder), &self.new_keyword)
+                        .on_input(Message::NewKeywordChanged)
+                        .on_submit(Message::AddKeyword)
+                        .into(),
+                    button(self.t(Text::AddKeyword))
+                        .on_press(Message::AddKeyword)
+                        .into(),
+                ])
+                .spacing(10)
+                .into(),
+            ])
+            .spacing(6)
+            .into(),
+            clear_button(MetadataField::Keywords),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    fn language_row(&self) -> Element<'_, Message> {
+        row(vec![
+            text(self.t(Text::DocumentLanguageLabel))
+                .width(Length::Fixed(120.0))
+                .into(),
+            text_input(
+                self.t(Text::DocumentLanguagePlaceholder),
+                &self.metadata.language,
+            )
+            .on_input(Message::DocumentLanguageChanged)
+            .into(),
+            // Free entry stays possible; the list only fills in the input
+            pick_list(
+                &COMMON_LANGUAGE_TAGS[..],
+                COMMON_LANGUAGE_TAGS
+                    .into_iter()
+                    .find(|tag| *tag == self.metadata.language),
+                |tag| Message::DocumentLanguageChanged(tag.to_string()),
+            )
+            .placeholder(self.t(Text::CommonLanguages))
+            .into(),
+            clear_button(MetadataField::Language),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    // A field as read-only mode shows it: the value as plain text, without
+    // an input or clear button
+    fn read_only_row(&self, label: Text, value: &str) -> Element<'_, Message> {
+        row(vec![
+            text(self.t(label)).width(Length::Fixed(120.0)).into(),
+            value_text(value).into(),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    // Joins an edited keyword list back with the separator the document used
+    fn set_keywords(&mut self, keywords: Vec<String>) {
+        let separator =
+            keyword_separator(&self.metadata.keywords).unwrap_or(self.keyword_separator);
+        self.metadata.keywords = keywords.join(separator);
+    }
+
+    fn text_row(
+        &self,
+        field: MetadataField,
+        placeholder: Text,
+        on_input: fn(String) -> Message,
+    ) -> Element<'_, Message> {
+        if self.preferences.read_only {
+            return self.read_only_row(field_label(field), self.metadata.get(field));
+        }
+        row(vec![
+            text(self.t(field_label(field)))
+                .width(Length::Fixed(120.0))
+                .into(),
+            text_input(self.t(placeholder), self.metadata.get(field))
+                .on_input(on_input)
+                .into(),
+            clear_button(field),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    // Like text_row, but flags non-numeric input right away instead of
+    // waiting for the save to reject it.
+    fn number_row(
+        &self,
+        field: MetadataField,
+        placeholder: Text,
+        on_input: fn(String) -> Message,
+    ) -> Element<'_, Message> {
+        let input = self.text_row(field, placeholder, on_input);
+        let value = self.metadata.get(field);
+        let (valid, error) = match field {
+            MetadataField::DocSecurity => (is_valid_doc_security(value), Text::InvalidDocSecurity),
+            _ => (is_valid_count(value), Text::InvalidNumber),
+        };
+        if value.trim().is_empty() || valid {
+            return input;
+        }
+        column(vec![
+            input,
+            text(self.t(error))
+                .size(14)
+                .style(self.error_color())
+                .into(),
+        ])
+        .spacing(4)
+        .into()
+    }
+
+    // The app.xml flags. A flag the part lacks shows unchecked and is only
+    // written once toggled.
+    fn flags_row(&self) -> Element<'_, Message> {
+        let flags = [
+            MetadataField::ScaleCrop,
+            MetadataField::LinksUpToDate,
+            MetadataField::SharedDoc,
+        ]
+        .map(|field| {
+            let label = self.t(field_label(field)).trim_end_matches(':');
+            // Left without a handler, and so disabled, in read-only mode
+            let flag = checkbox(label, is_flag_set(self.metadata.get(field)));
+            match self.preferences.read_only {
+                true => flag.into(),
+                false => flag
+                    .on_toggle(move |enabled| Message::FlagToggled(field, enabled))
+                    .into(),
+            }
+        });
+        row(flags).spacing(20).into()
+    }
+
+    // "Copy from..." / "Paste" plus the choice of fields a paste applies
+    fn clipboard_view(&self) -> Element<'_, Message> {
+        let copy_button = button(self.t(Text::CopyFrom)).on_press(Message::CopyFrom);
+        let mut paste_button = button(self.t(Text::PasteInto));
+        if self.clipboard.is_some() && self.file_path.is_some() && !self.preferences.read_only {
+            paste_button = paste_button.on_press(Message::PasteInto);
+        }
+        let source = self.clipboard.as_ref().map_or(String::new(), |(path, _)| {
+            fill(self.t(Text::CopiedFrom), &[&path.display().to_string()])
+        });
+
+        let mut rows = vec![row(vec![
+            copy_button.into(),
+            paste_button.into(),
+            value_text(source).size(14).into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()];
+        for fields in MetadataField::ALL.chunks(5) {
+            let checkboxes = fields.iter().map(|&field| {
+                let label = self.t(field_label(field)).trim_end_matches(':');
+                checkbox(label, self.paste_fields.contains(&field))
+                    .on_toggle(move |enabled| Message::PasteFieldToggled(field, enabled))
+                    .into()
+            });
+            rows.push(row(checkboxes.collect::<Vec<_>>()).spacing(20).into());
+        }
+        column(rows).spacing(10).into()
+    }
+
+    fn summary_view(&self) -> Element<'_, Message> {
+        let Some(summary) = &self.summary else {
+            return column(vec![]).into();
+        };
+        let yes_no = |present| self.t(if present { Text::Yes } else { Text::No });
+        // Rounded up so small files don't show as 0 KB
+        let size = summary.file_size.div_ceil(1024).to_string();
+        let entries = summary.entries.to_string();
+        let overview = text(if summary.kind.is_open_document() {
+            fill(
+                self.t(Text::OpenDocumentSummary),
+                &[&size, &entries, yes_no(summary.has_core_properties)],
+            )
+        } else {
+            fill(
+                self.t(Text::DocumentSummary),
+                &[
+                    &size,
+                    &entries,
+                    yes_no(summary.has_core_properties),
+                    yes_no(summary.has_app_properties),
+                    yes_no(summary.has_custom_properties),
+                    yes_no(summary.has_thumbnail),
+                ],
+            )
+        })
+        .size(14);
+        let Some(mismatch) = summary.parts_list_mismatch else {
+            return overview.into();
+        };
+        column(vec![
+            overview.into(),
+            text(fill(
+                self.t(Text::PartsListMismatch),
+                &[&mismatch.counted.to_string(), &mismatch.titles.to_string()],
+            ))
+            .size(14)
+            .style(self.error_color())
+            .into(),
+        ])
+        .into()
+    }
+
+    fn recent_files_view(&self) -> Element<'_, Message> {
+        if self.recent_files.is_empty() {
+            return column(vec![]).into();
+        }
+        let mut entries = vec![text(self.t(Text::RecentFiles)).size(16).into()];
+        entries.extend(self.recent_files.iter().map(|path| {
+            let mut entry = button(value_text(path.display().to_string()).size(14))
+                .style(iced::theme::Button::Text);
+            if !self.is_loading {
+                entry = entry.on_press(Message::FileSelected(Some(path.clone())));
+            }
+            entry.into()
+        }));
+        column(entries).spacing(4).into()
+    }
+
+    fn xml_preview_view(&self) -> Element<'_, Message> {
+        let mut toggle = button(self.t(if self.show_xml_preview {
+            Text::HideXml
+        } else {
+            Text::ShowXml
+        }));
+        if self.file_path.is_some() {
+            toggle = toggle.on_press(Message::ToggleXmlPreview);
+        }
+        if !self.show_xml_preview {
+            return toggle.into();
+        }
+
+        let pane = |title: &str, xml: &str| -> Element<'_, Message> {
+            column(vec![
+                text(title).size(14).into(),
+                scrollable(value_text(xml).size(12).font(Font::MONOSPACE))
+                    .height(Length::Fixed(160.0))
+                    .into(),
+            ])
+            .spacing(4)
+            .width(Length::Fill)
+            .into()
+        };
+        let preview = &self.xml_preview;
+        let parts = if self.show_xml_diff {
+            column(vec![
+                row(vec![
+                    pane(self.t(Text::XmlBefore), &preview.core),
+                    pane(self.t(Text::XmlAfter), &preview.pending_core),
+                ])
+                .spacing(10)
+                .into(),
+                row(vec![
+                    pane(self.t(Text::XmlBefore), &preview.app),
+                    pane(self.t(Text::XmlAfter), &preview.pending_app),
+                ])
+                .spacing(10)
+                .into(),
+            ])
+        } else {
+            column(vec![
+                pane("docProps/core.xml", &preview.core),
+                pane("docProps/app.xml", &preview.app),
+            ])
+        };
+
+        column(vec![
+            row(vec![
+                toggle.into(),
+                checkbox(self.t(Text::ShowXmlDiff), self.show_xml_diff)
+                    .on_toggle(Message::ShowXmlDiffToggled)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            parts.spacing(10).into(),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    fn settings_view(&self) -> Element<'_, Message> {
+        let toggle = button(self.t(if self.show_settings {
+            Text::HideSettings
+        } else {
+            Text::Settings
+        }))
+        .on_press(Message::ToggleSettings);
+        if !self.show_settings {
+            return toggle.into();
+        }
+
+        column(vec![
+            toggle.into(),
+            row(vec![
+                text(self.t(Text::Language)).into(),
+                pick_list(
+                    &Language::ALL[..],
+                    Some(self.preferences.language),
+                    Message::LanguageSelected,
+                )
+                .into(),
+                button(self.t(match self.preferences.theme {
+                    ThemeChoice::Light => Text::DarkTheme,
+                    ThemeChoice::Dark => Text::LightTheme,
+                }))
+                .on_press_maybe((!self.preferences.high_contrast).then_some(Message::ToggleTheme))
+                .into(),
+                checkbox(self.t(Text::HighContrast), self.preferences.high_contrast)
+                    .on_toggle(Message::HighContrastToggled)
+                    .into(),
+                checkbox(
+                    self.t(Text::LocalTimeDisplay),
+                    self.preferences.local_time_display,
+                )
+                .on_toggle(Message::LocalTimeDisplayToggled)
+                .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            row(vec![
+                text(self.t(Text::UiScale)).into(),
+                slider(
+                    config::UI_SCALE_RANGE,
+                    self.preferences.ui_scale,
+                    Message::UiScaleChanged,
+                )
+                .step(0.25)
+                .width(Length::Fixed(200.0))
+                .into(),
+                text(format!("{:.0}%", self.scale_factor() * 100.0)).into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            row(vec![
+                checkbox(
+                    self.t(Text::VerifyAfterSave),
+                    self.preferences.verify_after_save,
+                )
+                .on_toggle(Message::VerifyAfterSaveToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::ConfirmBeforeSave),
+                    self.preferences.confirm_before_save,
+                )
+                .on_toggle(Message::ConfirmBeforeSaveToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::BlockDateOrder),
+                    self.preferences.block_date_order,
+                )
+                .on_toggle(Message::BlockDateOrderToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::SyncFileTimes),
+                    self.preferences.sync_file_times,
+                )
+                .on_toggle(Message::SyncFileTimesToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::SyncCreatedTime),
+                    self.preferences.sync_created_time,
+                )
+                .on_toggle_maybe(
+                    self.preferences
+                        .sync_file_times
+                        .then_some(Message::SyncCreatedTimeToggled),
+                )
+                .into(),
+            ])
+            .spacing(20)
+            .into(),
+            row(vec![
+                checkbox(
+                    self.t(Text::CheckFutureDates),
+                    self.preferences.check_future_dates,
+                )
+                .on_toggle(Message::CheckFutureDatesToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::BlockFutureDates),
+                    self.preferences.block_future_dates,
+                )
+                .on_toggle_maybe(
+                    self.preferences
+                        .check_future_dates
+                        .then_some(Message::BlockFutureDatesToggled),
+                )
+                .into(),
+                text(self.t(Text::FutureDateTolerance)).into(),
+                text_input("5", &self.preferences.future_date_tolerance)
+                    .on_input(Message::FutureDateToleranceChanged)
+                    .width(Length::Fixed(60.0))
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            row(vec![
+                checkbox(self.t(Text::IndentXml), self.preferences.indent_xml)
+                    .on_toggle(Message::IndentXmlToggled)
+                    .into(),
+                checkbox(
+                    self.t(Text::LfLineEndings),
+                    self.preferences.lf_line_endings,
+                )
+                .on_toggle(Message::LfLineEndingsToggled)
+                .into(),
+                checkbox(
+                    self.t(Text::StampPartsModified),
+                    self.preferences.stamp_parts_modified,
+                )
+                .on_toggle_maybe(
+                    (!self.preferences.reproducible_zip)
+                        .then_some(Message::StampPartsModifiedToggled),
+                )
+                .into(),
+                checkbox(
+                    self.t(Text::ReproducibleZip),
+                    self.preferences.reproducible_zip,
+                )
+                .on_toggle(Message::ReproducibleZipToggled)
+                .into(),
+            ])
+            .spacing(20)
+            .into(),
+            row(vec![
+                text(self.t(Text::Compression)).into(),
+                pick_list(
+                    CompressionLevel::ALL
+                        .map(|level| self.compression_choice(level))
+                        .to_vec(),
+                    Some(self.compression_choice(self.preferences.compression)),
+                    Message::CompressionSelected,
+                )
+                .into(),
+                text(self.t(Text::DateProfile)).into(),
+                pick_list(
+                    DateProfile::ALL
+                        .map(|profile| self.date_profile_choice(profile))
+                        .to_vec(),
+                    Some(self.date_profile_choice(self.preferences.date_profile)),
+                    Message::DateProfileSelected,
+                )
+                .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+        ])
+        .spacing(10)
+        .into()
+    }
+
+    // Two documents' metadata side by side, read-only; the fields whose
+    // values differ are marked in red
+    fn comparison_view(&self) -> Element<'_, Message> {
+        let toggle = button(self.t(if self.show_comparison {
+            Text::HideComparison
+        } else {
+            Text::CompareDocuments
+        }))
+        .on_press(Message::ToggleComparison);
+        if !self.show_comparison {
+            return toggle.into();
+        }
+
+        let slots = self.compared.iter().enumerate().map(|(slot, compared)| {
+            let name = ["A", "B"][slot];
+            let path = compared
+                .as_ref()
+                .map_or(String::new(), |(path, _)| path.display().to_string());
+            row(vec![
+                button(value_text(fill(self.t(Text::ChooseComparedFile), &[name])))
+                    .on_press(Message::CompareSelect(slot))
+                    .into(),
+                value_text(path).size(14).into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into()
+        });
+        let mut lines: Vec<Element<'_, Message>> = vec![toggle.into()];
+        lines.extend(slots);
+
+        if let [Some((_, a)), Some((_, b))] = &self.compared {
+            let differing = MetadataField::ALL
+                .iter()
+                .filter(|field| a.get(**field) != b.get(**field))
+                .count();
+            lines.push(
+                text(fill(
+                    self.t(Text::ComparisonDifferences),
+                    &[
+                        &differing.to_string(),
+                        &MetadataField::ALL.len().to_string(),
+                    ],
+                ))
+                .size(14)
+                .into(),
+            );
+            for field in MetadataField::ALL {
+                let (left, right) = (a.get(field), b.get(field));
+                let cell = |value: &str| {
+                    let cell = value_text(value.to_string()).size(14).width(Length::Fill);
+                    if left == right {
+                        cell
+                    } else {
+                        cell.style(self.error_color())
+                    }
+                };
+                lines.push(
+                    row(vec![
+                        text(self.t(field_label(field)))
+                            .size(14)
+                            .width(Length::Fixed(160.0))
+                            .into(),
+                        cell(left).into(),
+                        cell(right).into(),
+                    ])
+                    .spacing(10)
+                    .into(),
+                );
+            }
+        }
+        column(lines).spacing(6).into()
+    }
+
+    // Loads, undo and imports replace metadata.description wholesale, so the
+    // editor is rebuilt whenever it no longer shows the current value.
+    fn sync_description_editor(&mut self) {
+        if editor_text(&self.description_editor) != self.metadata.description {
+            self.description_editor = text_editor::Content::with_text(&self.metadata.description);
+        }
+    }
+
+    // Re-reads the parts shown in the preview pane; a no-op while it is closed.
+    fn refresh_xml_preview(&mut self) {
+        let Some(path) = self.file_path.clone().filter(|_| self.show_xml_preview) else {
+            self.xml_preview = XmlPreview::default();
+            return;
+        };
+        let inputs = (
+            self.preferences.language,
+            self.show_xml_diff,
+            self.metadata.clone(),
+            self.save_options(),
+        );
+        let source = match self.xml_preview.source.take() {
+            Some((read_from, parts)) if read_from == path => {
+                if self.xml_preview.shown_for.as_ref() == Some(&inputs) {
+                    self.xml_preview.source = Some((read_from, parts));
+                    return;
+                }
+                parts
+            }
+            _ => PropertyParts::read(&path),
+        };
+        let failed = |e: &DocxMetaError| {
+            fill(
+                self.t(Text::Error),
+                &[&error_message(self.preferences.language, e)],
+            )
+        };
+        let original = |part: Option<&str>| match part {
+            Some(xml) => xml.to_string(),
+            None => self.t(Text::PartMissing).to_string(),
+        };
+        let mut preview = match &source {
+            Ok(parts) => XmlPreview {
+                core: original(parts.core()),
+                app: original(parts.app()),
+                ..XmlPreview::default()
+            },
+            Err(e) => XmlPreview {
+                core: failed(e),
+                app: failed(e),
+                ..XmlPreview::default()
+            },
+        };
+        if let (true, Ok(parts)) = (self.show_xml_diff, &source) {
+            // An OpenDocument text has no app.xml edits to show
+            let open_document = self
+                .summary
+                .as_ref()
+                .is_some_and(|summary| summary.kind.is_open_document());
+            let options = &inputs.3;
+            match parts.saved_metadata(&self.metadata, options) {
+                Ok(metadata) => {
+                    preview.pending_core = parts
+                        .core_xml(&metadata, options)
+                        .unwrap_or_else(|e| failed(&e));
+                    preview.pending_app = if open_document {
+                        preview.app.clone()
+                    } else {
+                        parts
+                            .app_xml(&metadata, options)
+                            .unwrap_or_else(|e| failed(&e))
+                    };
+                }
+                // A value the save would refuse; both panes say why
+                Err(e) => {
+                    preview.pending_core = failed(&e);
+                    preview.pending_app = failed(&e);
+                }
+            }
+        }
+        preview.source = Some((path, source));
+        preview.shown_for = Some(inputs);
+        self.xml_preview = preview;
+    }
+
+    // Runs the save on a worker thread, streaming its progress back. A fresh
+    // cancel flag is used for every file so one cancel can't leak into the next.
+    fn start_save(&mut self, input: PathBuf, output: PathBuf) -> impl Stream<Item = SaveEvent> {
+        self.cancel_save = Arc::new(AtomicBool::new(false));
+        self.save_progress = None;
+        let cancel = self.cancel_save.clone();
+        let metadata = self.metadata.clone();
+        let options = self.save_options();
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            let mut report = |progress| {
+                let _ = sender.unbounded_send(SaveEvent::Progress(progress));
+                !cancel.load(Ordering::Relaxed)
+            };
+            let result =
+                save_metadata_with_progress(&input, &output, &metadata, &options, &mut report);
+            let _ = sender.unbounded_send(SaveEvent::Finished(result.map_err(Arc::new)));
+        });
+        receiver
+    }
+
+    // Reads the document on a worker thread, reporting each stage so the
+    // status line moves while a large archive is opened
+    fn start_load(&mut self, path: PathBuf) -> Command<Message> {
+        let language = self.preferences.language;
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            let _ = sender.unbounded_send(LoadEvent::Scanning);
+            let summary = document_summary(&path);
+            let _ = sender.unbounded_send(LoadEvent::ReadingProperties);
+            let load = || Ok((summary?, load_metadata(&path)?));
+            let result = load().map_err(|e| error_message(language, &e));
+            let _ = sender.unbounded_send(LoadEvent::Finished(Box::new(result)));
+        });
+        Command::run(receiver, |event| match event {
+            LoadEvent::Finished(result) => Message::FileLoaded(*result),
+            stage => Message::LoadProgressed(stage),
+        })
+    }
+
+    // The status line, with the spinner while loading and, for an Office
+    // package, whether its app.xml is edited or will be created
+    fn status_view(&self) -> Element<'_, Message> {
+        let status = value_text(&self.status_message).size(16);
+        if self.is_loading {
+            return row(vec![
+                text(SPINNER_FRAMES[self.spinner_frame])
+                    .size(16)
+                    .width(Length::Fixed(12.0))
+                    .into(),
+                status.into(),
+            ])
+            .spacing(8)
+            .into();
+        }
+        let app_part = match &self.summary {
+            Some(summary) if !summary.kind.is_open_document() => {
+                self.t(match summary.has_app_properties {
+                    true => Text::AppXmlExisting,
+                    false => Text::AppXmlNew,
+                })
+            }
+            _ => return status.into(),
+        };
+        row(vec![status.into(), text(app_part).size(14).into()])
+            .spacing(20)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+
+    // Whether the next save adds an app.xml the loaded document didn't have
+    fn creates_app_xml(&self) -> bool {
+        self.summary
+            .as_ref()
+            .is_some_and(|summary| !summary.kind.is_open_document() && !summary.has_app_properties)
+    }
+
+    // The summary shown between the first Save press and the confirming one
+    fn save_confirmation_view(&self) -> Element<'_, Message> {
+        let (Some(changes), Some(path)) = (&self.save_confirmation, &self.file_path) else {
+            return column(vec![]).into();
+        };
+        let mut lines: Vec<Element<'_, Message>> = vec![value_text(fill(
+            self.t(Text::ConfirmSavePrompt),
+            &[&path.display().to_string()],
+        ))
+        .into()];
+        if changes.is_empty() {
+            lines.push(text(self.t(Text::NoFieldChanges)).size(14).into());
+        }
+        for change in changes {
+            lines.push(value_text(self.change_line(change)).size(14).into());
+        }
+        if self.creates_app_xml() {
+            lines.push(text(self.t(Text::CreatingAppXml)).size(14).into());
+        }
+        lines.push(
+            button(self.t(Text::KeepEditing))
+                .on_press(Message::CancelSaveConfirmation)
+                .into(),
+        );
+        column(lines).spacing(4).into()
+    }
+
+    fn change_line(&self, change: &FieldChange) -> String {
+        let kind = match change.kind() {
+            ChangeKind::Added => Text::ChangeAdded,
+            ChangeKind::Changed => Text::ChangeChanged,
+            ChangeKind::Removed => Text::ChangeRemoved,
+        };
+        format!(
+            "{} {} \"{}\" → \"{}\"",
+            self.t(field_label(change.field)),
+            self.t(kind),
+            change.old,
+            change.new
+        )
+    }
+
+    fn close_prompt_view(&self) -> Element<'_, Message> {
+        if !self.confirming_close {
+            return column(vec![]).into();
+        }
+        // Saving from here goes through the same checks as the Save button
+        let mut save_button = button(self.t(Text::SaveAndClose));
+        if self.file_path.is_some()
+            && !self.is_loading
+            && !self.dry_run
+            && !self.unconfirmed_signature
+            && !self.preferences.read_only
+        {
+            save_button = save_button.on_press(Message::SaveAndClose);
+        }
+        row(vec![
+            text(self.t(Text::UnsavedChanges))
+                .style(self.error_color())
+                .into(),
+            save_button.into(),
+            button(self.t(Text::CloseWithoutSaving))
+                .on_press(Message::ConfirmClose)
+                .into(),
+            button(self.t(Text::Cancel))
+                .on_press(Message::CancelClose)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    fn revert_prompt_view(&self) -> Element<'_, Message> {
+        if !self.confirming_revert {
+            return column(vec![]).into();
+        }
+        row(vec![
+            text(self.t(Text::RevertPrompt)).into(),
+            button(self.t(Text::Revert))
+                .on_press(Message::ConfirmRevert)
+                .into(),
+            button(self.t(Text::KeepEditing))
+                .on_press(Message::CancelRevert)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    fn remove_custom_prompt_view(&self) -> Element<'_, Message> {
+        if !self.confirming_remove_custom {
+            return column(vec![]).into();
+        }
+        row(vec![
+            text(self.t(Text::RemoveCustomPropertiesPrompt))
+                .style(self.error_color())
+                .into(),
+            button(self.t(Text::RemoveCustomProperties))
+                .on_press(Message::ConfirmRemoveCustomProperties)
+                .into(),
+            button(self.t(Text::KeepEditing))
+                .on_press(Message::CancelRemoveCustomProperties)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    fn remove_thumbnail_prompt_view(&self) -> Element<'_, Message> {
+        if !self.confirming_remove_thumbnail {
+            return column(vec![]).into();
+        }
+        row(vec![
+            text(self.t(Text::RemoveThumbnailPrompt))
+                .style(self.error_color())
+                .into(),
+            button(self.t(Text::RemoveThumbnail))
+                .on_press(Message::ConfirmRemoveThumbnail)
+                .into(),
+            button(self.t(Text::KeepEditing))
+                .on_press(Message::CancelRemoveThumbnail)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    // The folder's documents before a batch: unchecked ones are skipped, and
+    // the rest are saved top to bottom
+    fn batch_selection_view(&self) -> Element<'_, Message> {
+        if self.batch_candidates.is_empty() {
+            return column(vec![]).into();
+        }
+        let last = self.batch_candidates.len() - 1;
+        let selected = self
+            .batch_candidates
+            .iter()
+            .filter(|(_, included)| *included)
+            .count();
+        let entries: Vec<Element<'_, Message>> = self
+            .batch_candidates
+            .iter()
+            .enumerate()
+            .map(|(index, (path, included))| {
+                row(vec![
+                    button("↑")
+                        .on_press_maybe(
+                            (index > 0).then(|| Message::MoveBatchCandidate(index, index - 1)),
+                        )
+                        .into(),
+                    button("↓")
+                        .on_press_maybe(
+                            (index < last).then(|| Message::MoveBatchCandidate(index, index + 1)),
+                        )
+                        .into(),
+                    checkbox(path.display().to_string(), *included)
+                        .on_toggle(move |checked| Message::BatchCandidateToggled(index, checked))
+                        .text_shaping(iced::widget::text::Shaping::Advanced)
+                        .into(),
+                ])
+                .spacing(6)
+                .align_items(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+        column(vec![
+            text(fill(
+                self.t(Text::BatchSelected),
+                &[
+                    &selected.to_string(),
+                    &self.batch_candidates.len().to_string(),
+                ],
+            ))
+            .into(),
+            container(scrollable(column(entries).spacing(2)))
+                .max_height(240.0)
+                .into(),
+            row(vec![
+                button(self.t(Text::SaveSelected))
+                    .on_press_maybe((selected > 0).then_some(Message::StartBatch))
+                    .into(),
+                button(self.t(Text::SelectAll))
+                    .on_press(Message::SelectAllBatchCandidates(true))
+                    .into(),
+                button(self.t(Text::SelectNone))
+                    .on_press(Message::SelectAllBatchCandidates(false))
+                    .into(),
+                button(self.t(Text::Cancel))
+                    .on_press(Message::CancelBatchSelection)
+                    .into(),
+            ])
+            .spacing(10)
+            .into(),
+        ])
+        .spacing(6)
+        .into()
+    }
+
+    // The fields the pending import would change, under the chosen mode
+    fn import_preview_view(&self) -> Element<'_, Message> {
+        let Some(sidecar) = &self.pending_import else {
+            return column(vec![]).into();
+        };
+        let imported = imported_metadata(&self.metadata, sidecar, self.merge_import);
+        let mut lines: Vec<Element<'_, Message>> = vec![
+            text(self.t(Text::ImportPreview)).into(),
+            checkbox(self.t(Text::MergeImport), self.merge_import)
+                .on_toggle(Message::MergeImportToggled)
+                .into(),
+        ];
+        let changes: Vec<FieldChange> = MetadataField::ALL
+            .into_iter()
+            .filter(|field| self.metadata.get(*field) != imported.get(*field))
+            .map(|field| FieldChange {
+                field,
+                old: self.metadata.get(field).to_string(),
+                new: imported.get(field).to_string(),
+            })
+            .collect();
+        if changes.is_empty() {
+            lines.push(text(self.t(Text::NoFieldChanges)).size(14).into());
+        }
+        for change in &changes {
+            lines.push(value_text(self.change_line(change)).size(14).into());
+        }
+        lines.push(
+            row(vec![
+                button(self.t(Text::ApplyImport))
+                    .on_press(Message::ApplyImport)
+                    .into(),
+                button(self.t(Text::Cancel))
+                    .on_press(Message::CancelImport)
+                    .into(),
+            ])
+            .spacing(10)
+            .into(),
+        );
+        column(lines).spacing(4).into()
+    }
+
+    fn signature_warning_view(&self) -> Element<'_, Message> {
+        if !self.unconfirmed_signature {
+            return column(vec![]).into();
+        }
+        row(vec![
+            text(self.t(Text::SignedDocumentWarning))
+                .style(self.error_color())
+                .into(),
+            button(self.t(Text::ProceedAnyway))
+                .on_press(Message::ConfirmEditSigned)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    fn log_view(&self) -> Element<'_, Message> {
+        if self.log.is_empty() {
+            return column(vec![]).into();
+        }
+        let entries = self
+            .log
+            .iter()
+            .map(|entry| {
+                let line = value_text(format!(
+                    "{} {}",
+                    entry.time.format("%H:%M:%S"),
+                    entry.message
+                ))
+                .size(14);
+                if entry.is_error {
+                    line.style(self.error_color()).into()
+                } else {
+                    line.into()
+                }
+            })
+            .collect::<Vec<_>>();
+        column(vec![
+            row(vec![
+                text(self.t(Text::Log)).size(16).into(),
+                button(self.t(Text::ClearLog))
+                    .on_press(Message::ClearLog)
+                    .into(),
+            ])
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            container(scrollable(column(entries).spacing(2)))
+                .height(Length::Fixed(150.0))
+                .into(),
+        ])
+        .spacing(6)
+        .into()
+    }
+
+    // Green when the checks passed, otherwise each problem in red
+    fn validation_view(&self) -> Element<'_, Message> {
+        let Some(problems) = &self.validation else {
+            return column(vec![]).into();
+        };
+        if problems.is_empty() {
+            return text(self.t(Text::ValidationPassed))
+                .size(14)
+                .style(self.success_color())
+                .into();
+        }
+        column(
+            problems
+                .iter()
+                .map(|problem| {
+                    value_text(problem)
+                        .size(14)
+                        .style(self.error_color())
+                        .into()
+                })
+                .collect::<Vec<_>>(),
+        )
+        .spacing(2)
+        .into()
+    }
+
+    fn save_progress_view(&self) -> Element<'_, Message> {
+        let Some(progress) = self.save_progress else {
+            return column(vec![]).into();
+        };
+        let detail = fill(
+            self.t(Text::SaveProgress),
+            &[
+                &progress.entries_done.to_string(),
+                &progress.entries_total.to_string(),
+                &(progress.bytes_done / 1024).to_string(),
+                &(progress.bytes_total / 1024).to_string(),
+            ],
+        );
+        row(vec![
+            progress_bar(0.0..=1.0, progress.fraction())
+                .width(Length::Fixed(300.0))
+                .into(),
+            text(detail).size(14).into(),
+            button(self.t(Text::Cancel))
+                .on_press(Message::CancelSave)
+                .into(),
+        ])
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    fn save_options(&self) -> SaveOptions {
+        SaveOptions {
+            anonymize: self.anonymize,
+            clear_last_modified_by: self.strip_history,
+            remove_custom_properties: self.remove_custom,
+            remove_thumbnail: self.remove_thumbnail,
+            remove_fields: self.cleared_fields.clone(),
+            verify: self.preferences.verify_after_save,
+            sync_file_times: self.preferences.sync_file_times,
+            sync_created_time: self.preferences.sync_created_time,
+            block_date_order: self.preferences.block_date_order,
+            future_dates: match (
+                self.preferences.check_future_dates,
+                self.preferences.block_future_dates,
+            ) {
+                (false, _) => FutureDatePolicy::Ignore,
+                (true, false) => FutureDatePolicy::Warn,
+                (true, true) => FutureDatePolicy::Block,
+            },
+            // An unreadable tolerance counts as none, which errs on the strict side
+            future_date_tolerance: self
+                .preferences
+                .future_date_tolerance
+                .trim()
+                .parse()
+                .ok()
+                .and_then(Duration::try_minutes)
+                .unwrap_or_default(),
+            indent_xml: self.preferences.indent_xml,
+            lf_line_endings: self.preferences.lf_line_endings,
+            stamp_parts_modified: self.preferences.stamp_parts_modified,
+            reproducible_zip: self.preferences.reproducible_zip,
+            compression: self.preferences.compression,
+            date_profile: self.preferences.date_profile,
+            transforms: Vec::new(),
+        }
+    }
+
+    // Saves that succeeded but had to rebuild a part say so in the log and
+    // status line, as the rebuilt part loses whatever else it held
+    fn warn_save_report(&mut self, path: &Path, report: SaveReport) {
+        if report.app_rebuilt {
+            self.set_error(fill(
+                self.t(Text::AppPropertiesRebuilt),
+                &[&path.display().to_string()],
+            ));
+        }
+        for field in report.future_dates {
+            self.set_error(fill(
+                self.t(Text::FutureDateSaved),
+                &[self.t(field_label(field)), &path.display().to_string()],
+            ));
+        }
+    }
+
+    // Shows the fields as the save wrote them, normalization included. The
+    // undo history stays: re-reading the file is not an edit.
+    fn reload_saved(&mut self, path: &Path) {
+        self.xml_preview = XmlPreview::default();
+        match load_metadata(path) {
+            Ok(metadata) => self.metadata = metadata,
+            Err(e) => self.set_error(fill(
+                self.t(Text::ReloadAfterSaveFailed),
+                &[
+                    &path.display().to_string(),
+                    &error_message(self.preferences.language, &e),
+                ],
+            )),
+        }
+    }
+
+    // Kept apart from the format errors: these dates are valid, just unlikely
+    fn log_date_order_issues(&mut self) {
+        for issue in date_order_issues(&self.metadata) {
+            self.log_entry(self.t(date_order_label(issue)).to_string(), true);
+        }
+    }
+
+    fn date_order_view(&self) -> Element<'_, Message> {
+        column(
+            date_order_issues(&self.metadata)
+                .into_iter()
+                .map(|issue| {
+                    text(self.t(date_order_label(issue)))
+                        .size(14)
+                        .style(self.error_color())
+                        .into()
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            metadata: self.metadata.clone(),
+            anonymize: self.anonymize,
+            strip_history: self.strip_history,
+            remove_custom: self.remove_custom,
+            remove_thumbnail: self.remove_thumbnail,
+            cleared_fields: self.cleared_fields.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.metadata = snapshot.metadata;
+        self.anonymize = snapshot.anonymize;
+        self.strip_history = snapshot.strip_history;
+        self.remove_custom = snapshot.remove_custom;
+        self.remove_thumbnail = snapshot.remove_thumbnail;
+        self.cleared_fields = snapshot.cleared_fields;
+        self.dirty = true;
+        self.save_confirmation = None;
+        self.validation = None;
+    }
+
+    // Only populates the inputs; nothing is written until the user saves. An
+    // import that changes nothing isn't recorded as an edit.
+    fn apply_import(&mut self, sidecar: &MetadataSidecar, merge: bool) {
+        let imported = imported_metadata(&self.metadata, sidecar, merge);
+        if imported != self.metadata {
+            self.record_edit();
+            self.metadata = imported;
+        }
+        self.set_status(self.t(Text::JsonImported).to_string());
+    }
+
+    // Call before applying a user edit; a fresh edit invalidates redo.
+    fn record_edit(&mut self) {
+        self.dirty = true;
+        self.save_confirmation = None;
+        self.validation = None;
+        self.undo_stack.push_back(self.snapshot());
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    fn metadata_sidecar(&self, path: &Path) -> MetadataSidecar {
+        MetadataSidecar {
+            schema_version: METADATA_SCHEMA_VERSION,
+            source_path: path.display().to_string(),
+            created: non_empty(&self.metadata.created),
+            modified: non_empty(&self.metadata.modified),
+            last_printed: non_empty(&self.metadata.last_printed),
+            revision: non_empty(&self.metadata.revision),
+            content_status: non_empty(&self.metadata.content_status),
+            description: non_empty(&self.metadata.description),
+            keywords: non_empty(&self.metadata.keywords),
+            language: non_empty(&self.metadata.language),
+            category: non_empty(&self.metadata.category),
+            version: non_empty(&self.metadata.version),
+            identifier: non_empty(&self.metadata.identifier),
+            template: non_empty(&self.metadata.template),
+            application: non_empty(&self.metadata.application),
+            app_version: non_empty(&self.metadata.app_version),
+            company: non_empty(&self.metadata.company),
+            manager: non_empty(&self.metadata.manager),
+            total_time: non_empty(&self.metadata.total_time),
+            pages: non_empty(&self.metadata.pages),
+            words: non_empty(&self.metadata.words),
+            characters: non_empty(&self.metadata.characters),
+            doc_security: non_empty(&self.metadata.doc_security),
+            scale_crop: non_empty(&self.metadata.scale_crop),
+            links_up_to_date: non_empty(&self.metadata.links_up_to_date),
+            shared_doc: non_empty(&self.metadata.shared_doc),
+        }
+    }
+
+    // Saves the next queued batch file with the values currently in the
+    // editor, or wraps up the batch once the queue is empty.
+    fn save_next_batch_file(&mut self) -> Command<Message> {
+        match self.batch_queue.pop() {
+            Some(path) => {
+                Command::run(
+                    self.start_save(path.clone(), path.clone()),
+                    move |event| match event {
+                        SaveEvent::Progress(progress) => Message::SaveProgressed(progress),
+                        SaveEvent::Finished(result) => {
+                            Message::BatchFileSaved(path.clone(), result)
+                        }
+                    },
+                )
+            }
+            None => {
+                self.is_loading = false;
+                // Counted from the report, as a cancel can leave files unprocessed
+                let succeeded = self.batch_report.len() - self.batch_failures;
+                self.set_status(fill(
+                    self.t(Text::BatchFinished),
+                    &[&succeeded.to_string(), &self.batch_failures.to_string()],
+                ));
+                Command::none()
+            }
+        }
+    }
+}
+
+// Empties a field; saving then drops its element instead of writing it empty
+fn clear_button<'a>(field: MetadataField) -> Element<'a, Message> {
+    button("×").on_press(Message::ClearField(field)).into()
+}
+
+fn field_label(field: MetadataField) -> Text {
+    match field {
+        MetadataField::Created => Text::CreatedLabel,
+        MetadataField::Modified => Text::ModifiedLabel,
+        MetadataField::LastPrinted => Text::LastPrintedLabel,
+        MetadataField::Revision => Text::RevisionLabel,
+        MetadataField::ContentStatus => Text::ContentStatusLabel,
+        MetadataField::Description => Text::DescriptionLabel,
+        MetadataField::Keywords => Text::KeywordsLabel,
+        MetadataField::Language => Text::DocumentLanguageLabel,
+        MetadataField::Category => Text::CategoryLabel,
+        MetadataField::Version => Text::VersionLabel,
+        MetadataField::Identifier => Text::IdentifierLabel,
+        MetadataField::Template => Text::TemplateLabel,
+        MetadataField::Application => Text::ApplicationLabel,
+        MetadataField::AppVersion => Text::AppVersionLabel,
+        MetadataField::Company => Text::CompanyLabel,
+        MetadataField::Manager => Text::ManagerLabel,
+        MetadataField::TotalTime => Text::TotalTimeLabel,
+        MetadataField::Pages => Text::PagesLabel,
+        MetadataField::Words => Text::WordsLabel,
+        MetadataField::Characters => Text::CharactersLabel,
+        MetadataField::DocSecurity => Text::DocSecurityLabel,
+        MetadataField::ScaleCrop => Text::ScaleCropLabel,
+        MetadataField::LinksUpToDate => Text::LinksUpToDateLabel,
+        MetadataField::SharedDoc => Text::SharedDocLabel,
+    }
+}
+
+fn date_order_label(issue: DateOrderIssue) -> Text {
+    match issue {
+        DateOrderIssue::ModifiedBeforeCreated => Text::ModifiedBeforeCreated,
+        DateOrderIssue::LastPrintedBeforeCreated => Text::LastPrintedBeforeCreated,
+    }
+}
+
+// A library error as the GUI and the CLI show it, in `language`; the
+// library's own Display is English only
+fn error_message(language: Language, error: &DocxMetaError) -> String {
+    let t = |key| tr(language, key);
+    let name = |field| t(field_label(field)).trim_end_matches(':');
+    match error {
+        DocxMetaError::Io(e) => e.to_string(),
+        DocxMetaError::Zip(e) => e.to_string(),
+        DocxMetaError::Xml {
+            part,
+            message,
+            position: None,
+        } => fill(t(Text::XmlError), &[part, message]),
+        DocxMetaError::Xml {
+            part,
+            message,
+            position: Some(position),
+        } => fill(
+            t(Text::XmlErrorAt),
+            &[
+                part,
+                &position.line.to_string(),
+                &position.column.to_string(),
+                message,
+            ],
+        ),
+        DocxMetaError::Undecodable { part, encoding } => {
+            fill(t(Text::UndecodablePart), &[part, encoding])
+        }
+        DocxMetaError::DateParse { field, value } => {
+            fill(t(Text::DateParseError), &[name(*field), value])
+        }
+        DocxMetaError::NotWordDate { field, value } => {
+            fill(t(Text::NotWordDate), &[name(*field), value])
+        }
+        DocxMetaError::InvalidCount { field, value } => {
+            fill(t(Text::CountParseError), &[name(*field), value])
+        }
+        DocxMetaError::InvalidFlag { field, value } => {
+            fill(t(Text::FlagParseError), &[name(*field), value])
+        }
+        DocxMetaError::InvalidDocSecurity(value) => fill(
+            t(Text::DocSecurityParseError),
+            &[name(MetadataField::DocSecurity), value],
+        ),
+        DocxMetaError::InvalidAppVersion(value) => fill(
+            t(Text::AppVersionParseError),
+            &[name(MetadataField::AppVersion), value],
+        ),
+        DocxMetaError::DateOrder(issue) => fill(
+            t(Text::DatesOutOfOrder),
+            &[t(match issue {
+                DateOrderIssue::ModifiedBeforeCreated => Text::ModifiedBeforeCreatedIssue,
+                DateOrderIssue::LastPrintedBeforeCreated => Text::LastPrintedBeforeCreatedIssue,
+            })],
+        ),
+        DocxMetaError::FutureDates(fields) => {
+            let names: Vec<&str> = fields.iter().map(|field| name(*field)).collect();
+            fill(t(Text::FutureDatesRefused), &[&names.join(", ")])
+        }
+        DocxMetaError::PartModified(part) => fill(t(Text::PartModified), &[part]),
+        DocxMetaError::NotADocx(reason) => match reason {
+            NotADocxReason::Unreadable(e) => fill(t(Text::UnreadableArchive), &[e]),
+            NotADocxReason::Encrypted => t(Text::EncryptedDocument).to_string(),
+            NotADocxReason::LegacyBinary => t(Text::LegacyBinaryDocument).to_string(),
+            NotADocxReason::OpenDocument(mimetype) => {
+                fill(t(Text::UnsupportedOpenDocument), &[mimetype])
+            }
+            NotADocxReason::NoMainPart => t(Text::NoMainDocumentPart).to_string(),
+        },
+        DocxMetaError::MissingPart(part) => fill(t(Text::MissingPackagePart), &[part]),
+        DocxMetaError::Cancelled => t(Text::SaveCancelled).to_string(),
+        DocxMetaError::Context { step, source } => {
+            let step = match step {
+                SaveStep::ReplaceOriginal => t(Text::ReplaceOriginalFailed).to_string(),
+                SaveStep::WriteNewFile => t(Text::WriteNewFileFailed).to_string(),
+                SaveStep::CreateBackup => t(Text::CreateBackupFailed).to_string(),
+                SaveStep::CopyAcrossVolumes(e) => {
+                    fill(t(Text::CrossVolumeCopyFailed), &[&e.to_string()])
+                }
+                SaveStep::RemoveTempFile => t(Text::RemoveTempFileFailed).to_string(),
+                SaveStep::SetFileTimes => t(Text::SetFileTimesFailed).to_string(),
+                SaveStep::VerifyRemovedNew => t(Text::VerifyRemovedNew).to_string(),
+                SaveStep::VerifyRestored => t(Text::VerifyRestored).to_string(),
+                SaveStep::VerifyRestoreFailed(e) => {
+                    fill(t(Text::VerifyRestoreFailed), &[&error_message(language, e)])
+                }
+            };
+            format!("{}: {}", step, error_message(language, source))
+        }
+    }
+}
+
+// Moves `current` onto `date`, keeping its time of day and offset (midnight
+// UTC if unset)
+fn date_with_day(current: &str, date: NaiveDate) -> String {
+    let current = parse_date_with_offset(current);
+    let local = date.and_time(current.map_or(NaiveTime::MIN, |dt| dt.time()));
+    current
+        .and_then(|dt| dt.offset().from_local_datetime(&local).single())
+        .unwrap_or_else(|| local.and_utc().fixed_offset())
+        .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+// A stored date in the system timezone, with the offset in force at that
+// instant, so dates on either side of a DST change each show their own
+fn local_display(value: &str) -> Option<String> {
+    let date = DateTime::parse_from_rfc3339(value.trim()).ok()?;
+    Some(
+        date.with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, false),
+    )
+}
+
+// Typed into a date input showing local time; gives the UTC value to store.
+// A wall-clock time without an offset is read in the system timezone: when
+// clocks go back and it occurs twice the earlier one is taken, and when
+// they go forward and it never occurs there is nothing to convert.
+fn local_input_to_utc(value: &str) -> Option<String> {
+    let value = value.trim();
+    let date = match DateTime::parse_from_rfc3339(value) {
+        Ok(date) => date.with_timezone(&Utc),
+        Err(_) => {
+            let wall_clock = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").ok()?;
+            Local
+                .from_local_datetime(&wall_clock)
+                .earliest()?
+                .with_timezone(&Utc)
+        }
+    };
+    Some(date.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+// date_with_day for an input showing local time: the local time of day is
+// kept on the picked day, whose offset may differ across a DST change
+fn local_date_with_day(current: &str, date: NaiveDate) -> String {
+    let time = parse_date_with_offset(current)
+        .map_or(NaiveTime::MIN, |dt| dt.with_timezone(&Local).time());
+    match Local.from_local_datetime(&date.and_time(time)).earliest() {
+        Some(picked) => picked
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        None => date_with_day(current, date),
+    }
+}
+
+// For text that can hold metadata values or file names, which may be in any
+// script. The default basic shaping neither joins Arabic letters nor lays
+// out right-to-left runs, and won't fall back to a system font for glyphs
+// the bundled Noto Sans SC lacks; text inputs already shape this way.
+fn value_text<'a>(content: impl ToString) -> iced::widget::Text<'a> {
+    text(content).shaping(iced::widget::text::Shaping::Advanced)
+}
+
+// Opens the folder holding `path` in the platform's file manager, with the
+// file selected where the file manager supports that
+fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("explorer");
+        // explorer expects "/select," and the path as a single argument
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        command.arg(select);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = std::process::Command::new("xdg-open");
+        let folder = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        command.arg(folder.unwrap_or(Path::new(".")));
+        command
+    };
+    // Not waited for: the file manager keeps running on its own
+    command.spawn().map(|_| ())
+}
+
+// Content::text always ends in a newline that isn't part of the value
+fn editor_text(content: &text_editor::Content) -> String {
+    let text = content.text();
+    text.strip_suffix('\n').unwrap_or(&text).to_string()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn kind_label(kind: DocumentKind) -> Text {
+    match kind {
+        DocumentKind::Word => Text::WordDocument,
+        DocumentKind::Excel => Text::ExcelWorkbook,
+        DocumentKind::PowerPoint => Text::PowerPointPresentation,
+        DocumentKind::OpenDocumentText => Text::OdtDocument,
+    }
+}
+
+// File extensions of the packages we can edit. Macro-enabled documents and
+// Word templates carry the same main part and docProps as a .docx; the
+// library tells an .odt apart by its content.
+const DOCUMENT_EXTENSIONS: [&str; 7] = ["docx", "docm", "dotx", "dotm", "xlsx", "pptx", "odt"];
+
+fn is_supported_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            DOCUMENT_EXTENSIONS
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported))
+        })
+}
+
+// Office keeps "~$name.docx" owner files next to open documents; they are not
+// zip archives, so leave them out of batches.
+fn list_documents(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_supported_document(path))
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("~$"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// --- Async Helper Functions ---
+
+async fn select_file_async(filter_name: &str) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter(filter_name, &DOCUMENT_EXTENSIONS)
+        .pick_file()
+}
+
+// Suggests "<name>-edited.<ext>" next to the original
+async fn select_save_path_async(path: PathBuf) -> Option<PathBuf> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let mut dialog = FileDialog::new()
+        .add_filter(extension.to_uppercase(), &[extension.as_ref()])
+        .set_file_name(format!("{}-edited.{}", stem, extension));
+    if let Some(dir) = path.parent() {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog.save_file()
+}
+
+async fn select_json_async(filter_name: &str) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter(filter_name, &["json"])
+        .pick_file()
+}
+
+async fn select_csv_path_async(filter_name: &str) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter(filter_name, &["csv"])
+        .set_file_name("metadata.csv")
+        .save_file()
+}
+
+async fn select_folder_async() -> Option<PathBuf> {
+    FileDialog::new().pick_folder()
+}
+
+async fn dry_run_async(
+    path: PathBuf,
+    metadata: CoreMetadata,
+    options: SaveOptions,
+    language: Language,
+) -> Result<Vec<FieldChange>, String> {
+    dry_run(&path, &metadata, &options).map_err(|e| error_message(language, &e))
+}
+
+async fn validate_async(
+    path: PathBuf,
+    metadata: CoreMetadata,
+    options: SaveOptions,
+    language: Language,
+) -> Vec<String> {
+    validate(&path, &metadata, &options)
+        .iter()
+        .map(|e| error_message(language, e))
+        .collect()
+}
+
+async fn load_metadata_async(path: PathBuf, language: Language) -> Result<CoreMetadata, String> {
+    load_metadata(&path).map_err(|e| error_message(language, &e))
+}
+
+// Sends a tick every SPINNER_INTERVAL until the receiver is dropped
+fn spinner_ticks() -> mpsc::UnboundedReceiver<()> {
+    let (sender, receiver) = mpsc::unbounded();
+    thread::spawn(move || {
+        while sender.unbounded_send(()).is_ok() {
+            thread::sleep(SPINNER_INTERVAL);
+        }
+    });
+    receiver
+}
+
+// Writes `<name>.meta.json` next to the document
+async fn export_json(path: PathBuf, sidecar: MetadataSidecar) -> Result<PathBuf, String> {
+    let json_path = path.with_extension("meta.json");
+    let json = serde_json::to_string_pretty(&sidecar).map_err(|e| e.to_string())?;
+    fs::write(&json_path, json).map_err(|e| e.to_string())?;
+    Ok(json_path)
+}
+
+// Files that can't be read are left out and returned as "<path>: <error>".
+// The UTF-8 byte order mark is what makes Excel read the file as UTF-8.
+async fn export_csv(
+    files: Vec<PathBuf>,
+    csv_path: PathBuf,
+    language: Language,
+) -> Result<(PathBuf, Vec<String>), String> {
+    let mut records = Vec::new();
+    let mut skipped = Vec::new();
+    for path in files {
+        match load_record(&path) {
+            Ok(record) => records.push(record),
+            Err(e) => skipped.push(format!(
+                "{}: {}",
+                path.display(),
+                error_message(language, &e)
+            )),
+        }
+    }
+    let csv = format!("\u{feff}{}", records_to_csv(&records));
+    fs::write(&csv_path, csv).map_err(|e| e.to_string())?;
+    Ok((csv_path, skipped))
+}
+
+// The metadata an import leaves: replacing takes every field from the JSON,
+// null ones becoming empty, while merging takes only the non-null ones
+fn imported_metadata(
+    current: &CoreMetadata,
+    sidecar: &MetadataSidecar,
+    merge: bool,
+) -> CoreMetadata {
+    let mut metadata = current.clone();
+    for field in MetadataField::ALL {
+        match sidecar_value(sidecar, field) {
+            Some(value) => *metadata.get_mut(field) = value.clone(),
+            None if !merge => metadata.get_mut(field).clear(),
+            None => {}
+        }
+    }
+    metadata
+}
+
+fn sidecar_value(sidecar: &MetadataSidecar, field: MetadataField) -> &Option<String> {
+    match field {
+        MetadataField::Created => &sidecar.created,
+        MetadataField::Modified => &sidecar.modified,
+        MetadataField::LastPrinted => &sidecar.last_printed,
+        MetadataField::Revision => &sidecar.revision,
+        MetadataField::ContentStatus => &sidecar.content_status,
+        MetadataField::Description => &sidecar.description,
+        MetadataField::Keywords => &sidecar.keywords,
+        MetadataField::Language => &sidecar.language,
+        MetadataField::Category => &sidecar.category,
+        MetadataField::Version => &sidecar.version,
+        MetadataField::Identifier => &sidecar.identifier,
+        MetadataField::Template => &sidecar.template,
+        MetadataField::Application => &sidecar.application,
+        MetadataField::AppVersion => &sidecar.app_version,
+        MetadataField::Company => &sidecar.company,
+        MetadataField::Manager => &sidecar.manager,
+        MetadataField::TotalTime => &sidecar.total_time,
+        MetadataField::Pages => &sidecar.pages,
+        MetadataField::Words => &sidecar.words,
+        MetadataField::Characters => &sidecar.characters,
+        MetadataField::DocSecurity => &sidecar.doc_security,
+        MetadataField::ScaleCrop => &sidecar.scale_crop,
+        MetadataField::LinksUpToDate => &sidecar.links_up_to_date,
+        MetadataField::SharedDoc => &sidecar.shared_doc,
+    }
+}
+
+// Why a sidecar was refused; update words it in the interface language
+#[derive(Debug, Clone)]
+enum ImportError {
+    Read(String),                                 // The file couldn't be read
+    Parse(String),                                // serde_json's message, with line and column
+    UnknownFields(Vec<(String, Option<String>)>), // As unknown_sidecar_fields gives them
+    SchemaVersion(u32),
+    // A value the field can't hold; `kind` is the template naming the rule
+    InvalidValue {
+        kind: Text,
+        field: &'static str,
+        value: String,
+    },
+}
+
+// The keys of a sidecar object that MetadataSidecar doesn't have, each with
+// the field it was probably meant to be when one is close enough
+fn unknown_sidecar_fields(value: &serde_json::Value) -> Vec<(String, Option<String>)> {
+    let known = match serde_json::to_value(MetadataSidecar::default()) {
+        Ok(serde_json::Value::Object(known)) => known,
+        _ => return Vec::new(),
+    };
+    let Some(object) = value.as_object() else {
+        return Vec::new();
+    };
+    object
+        .keys()
+        .filter(|key| !known.contains_key(*key))
+        .map(|key| {
+            let closest = known
+                .keys()
+                .map(|name| (edit_distance(key, name), name))
+                .min()
+                .filter(|(distance, _)| *distance <= 2.max(key.len() / 4));
+            (key.clone(), closest.map(|(_, name)| name.clone()))
+        })
+        .collect()
+}
+
+// Levenshtein distance, case-insensitively and counting characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+async fn import_json(json_path: PathBuf) -> Result<MetadataSidecar, ImportError> {
+    let json = fs::read_to_string(&json_path).map_err(|e| ImportError::Read(e.to_string()))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| ImportError::Parse(e.to_string()))?;
+    let unknown = unknown_sidecar_fields(&value);
+    if !unknown.is_empty() {
+        return Err(ImportError::UnknownFields(unknown));
+    }
+    // Parsed from the text again so type errors still report line and column
+    let sidecar: MetadataSidecar =
+        serde_json::from_str(&json).map_err(|e| ImportError::Parse(e.to_string()))?;
+    if sidecar.schema_version > METADATA_SCHEMA_VERSION {
+        return Err(ImportError::SchemaVersion(sidecar.schema_version));
+    }
+    let invalid = |kind, field: &'static str, value: &str| ImportError::InvalidValue {
+        kind,
+        field,
+        value: value.to_string(),
+    };
+    for (field, value) in [
+        ("created", &sidecar.created),
+        ("modified", &sidecar.modified),
+        ("last_printed", &sidecar.last_printed),
+    ] {
+        if let Some(value) = value {
+            if normalize_date(value).is_none() {
+                return Err(invalid(Text::JsonInvalidDate, field, value));
+            }
+        }
+    }
+    for (field, value) in [
+        ("revision", &sidecar.revision),
+        ("total_time", &sidecar.total_time),
+        ("pages", &sidecar.pages),
+        ("words", &sidecar.words),
+        ("characters", &sidecar.characters),
+    ] {
+        if let Some(value) = value {
+            if !is_valid_count(value) {
+                return Err(invalid(Text::JsonInvalidCount, field, value));
+            }
+        }
+    }
+    if let Some(value) = &sidecar.doc_security {
+        if !is_valid_doc_security(value) {
+            return Err(invalid(Text::JsonInvalidDocSecurity, "doc_security", value));
+        }
+    }
+    for (field, value) in [
+        ("scale_crop", &sidecar.scale_crop),
+        ("links_up_to_date", &sidecar.links_up_to_date),
+        ("shared_doc", &sidecar.shared_doc),
+    ] {
+        if let Some(value) = value {
+            if !is_valid_flag(value) {
+                return Err(invalid(Text::JsonInvalidFlag, field, value));
+            }
+        }
+    }
+    Ok(sidecar)
 }